@@ -0,0 +1,1353 @@
+use crate::course::{Course, Exercise};
+use crate::database::{Attempt, Database, ExerciseProgress};
+use crate::test_runner::{self, TestOutcome, TestResult, TestRunner};
+use crate::verify;
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, Semaphore};
+
+/// Exit codes of the non-interactive commands
+pub const EXIT_PASSED: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_ERROR: i32 = 2;
+pub const EXIT_TIMEOUT: i32 = 124;
+pub const EXIT_CANCELLED: i32 = 130;
+
+/// Flags, options and positional arguments of a subcommand
+struct CommandArgs {
+    flags: Vec<String>,
+    options: Vec<(String, String)>,
+    positional: Vec<String>,
+}
+
+impl CommandArgs {
+    /// Split arguments into boolean `flags`, `options` that take a value, and
+    /// positional arguments, rejecting anything else that starts with "--"
+    fn parse(args: &[String], flags: &[&str], options: &[&str], usage: &str) -> Result<Self> {
+        let mut parsed = Self {
+            flags: Vec::new(),
+            options: Vec::new(),
+            positional: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if flags.contains(&arg.as_str()) {
+                parsed.flags.push(arg.clone());
+            } else if options.contains(&arg.as_str()) {
+                let Some(value) = args.next() else {
+                    bail!("Option '{}' needs a value\n\nUsage: {}", arg, usage);
+                };
+                parsed.options.push((arg.clone(), value.clone()));
+            } else if arg.starts_with("--") {
+                bail!("Unknown option '{}'\n\nUsage: {}", arg, usage);
+            } else {
+                parsed.positional.push(arg.clone());
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+
+    /// Last value given for an option
+    fn value(&self, option: &str) -> Option<&str> {
+        self.values(option).last().copied()
+    }
+
+    /// Every value given for a repeatable option
+    fn values(&self, option: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(name, _)| name == option)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+}
+
+/// A loaded course together with its progress database
+struct LoadedCourse {
+    course: Course,
+    path: PathBuf,
+    exercises: Vec<Exercise>,
+    database: Database,
+}
+
+impl LoadedCourse {
+    fn load(course_dir: Option<&str>) -> Result<Self> {
+        let path = match course_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::current_dir()?,
+        };
+
+        if !path.join("course.json").exists() {
+            bail!(
+                "course.json not found in {:?}. Run from a course directory or pass its path.",
+                path
+            );
+        }
+
+        let (course, exercises) = Course::load_from_path(&path)?;
+        let database = Database::open(&path, &exercises)?;
+
+        Ok(Self {
+            course,
+            path,
+            exercises,
+            database,
+        })
+    }
+
+    fn exercises_dir(&self) -> PathBuf {
+        self.path.join("exercises")
+    }
+
+    fn progress(&self) -> Result<HashMap<String, ExerciseProgress>> {
+        Ok(self
+            .database
+            .get_all_progress()?
+            .into_iter()
+            .map(|p| (p.exercise_id.clone(), p))
+            .collect())
+    }
+}
+
+fn completion_map(progress: &HashMap<String, ExerciseProgress>) -> HashMap<String, bool> {
+    progress
+        .iter()
+        .map(|(id, p)| (id.clone(), p.completed))
+        .collect()
+}
+
+/// Print a command's output. A closed pipe (e.g. `learnp list | head`) is not an error.
+fn emit(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    match writeln!(stdout, "{}", text).and_then(|_| stdout.flush()) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.context("Failed to write output"),
+    }
+}
+
+fn unblock_all_requested(args: &CommandArgs) -> bool {
+    args.has("--unblock-all") || std::env::var("LEARNP_UNBLOCK_ALL").unwrap_or_default() == "1"
+}
+
+/// `learnp test <exercise> [course-directory]`
+///
+/// Runs one exercise's tests, streaming the output to stdout, records the result
+/// and returns the process exit code.
+pub async fn test_command(args: &[String]) -> Result<i32> {
+    const USAGE: &str = "learnp test <exercise> [course-directory]";
+    let args = CommandArgs::parse(args, &[], &[], USAGE)?;
+    let (query, course_dir) = match args.positional.as_slice() {
+        [query] => (query.as_str(), None),
+        [query, course_dir] => (query.as_str(), Some(course_dir.as_str())),
+        _ => bail!("Usage: {}", USAGE),
+    };
+
+    let course = LoadedCourse::load(course_dir)?;
+    let exercise = find_exercise(&course.exercises, &course.path, query)?;
+    let test_runner = TestRunner::new(&course.path);
+
+    let (output_tx, mut output_rx) = mpsc::channel::<String>(100);
+    let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+
+    let printer = tokio::spawn(async move {
+        let mut stdout = std::io::stdout();
+        while let Some(chunk) = output_rx.recv().await {
+            if test_runner::is_setup_marker(&chunk) {
+                continue;
+            }
+            let _ = stdout.write_all(chunk.as_bytes());
+            let _ = stdout.flush();
+        }
+    });
+
+    // Tests run in their own process group, so Ctrl+C has to be forwarded
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = cancel_tx.send(()).await;
+        }
+    });
+
+    let outcome = test_runner
+        .run_test_streaming(exercise, output_tx, &mut cancel_rx)
+        .await;
+    ctrl_c.abort();
+    let _ = printer.await;
+
+    let outcome = match outcome {
+        Ok(outcome) => outcome,
+        Err(e) => TestOutcome::new(TestResult::Error(format!("Failed to run test: {:#}", e))),
+    };
+
+    course
+        .database
+        .record_outcome(&exercise.id, &outcome, false)
+        .context("Failed to record test result")?;
+
+    println!();
+    println!("{}", outcome_line(exercise, &outcome));
+    Ok(exit_code(&outcome.result))
+}
+
+/// One exercise or folder in `learnp list` output
+#[derive(Debug, Serialize)]
+struct ListEntry {
+    id: String,
+    title: String,
+    kind: &'static str,
+    completed: bool,
+    locked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_result: Option<String>,
+    /// Completed and total exercises inside a folder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<FolderCounts>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<ListEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct FolderCounts {
+    completed: usize,
+    total: usize,
+}
+
+/// `learnp list [--json] [--unblock-all] [course-directory]`
+///
+/// Prints the course tree with completion and lock state.
+pub fn list_command(args: &[String]) -> Result<i32> {
+    const USAGE: &str = "learnp list [--json] [--unblock-all] [course-directory]";
+    let args = CommandArgs::parse(args, &["--json", "--unblock-all"], &[], USAGE)?;
+    if args.positional.len() > 1 {
+        bail!("Usage: {}", USAGE);
+    }
+
+    let course = LoadedCourse::load(args.positional.first().map(String::as_str))?;
+    let progress = course.progress()?;
+    let entries = list_entries(
+        &course.exercises,
+        &course.exercises_dir(),
+        &progress,
+        false,
+        unblock_all_requested(&args),
+    );
+
+    if args.has("--json") {
+        emit(&serde_json::to_string_pretty(&entries)?)?;
+    } else {
+        let mut text = course.course.name.clone();
+        format_entries(&entries, 0, &mut text);
+        emit(&text)?;
+    }
+    Ok(EXIT_PASSED)
+}
+
+/// Build the tree below `folder_path`. Locking follows the TUI: within a folder,
+/// nothing after the first incomplete exercise can be opened.
+fn list_entries(
+    exercises: &[Exercise],
+    folder_path: &Path,
+    progress: &HashMap<String, ExerciseProgress>,
+    parent_locked: bool,
+    unblock_all: bool,
+) -> Vec<ListEntry> {
+    let completion = completion_map(progress);
+    let siblings: Vec<&Exercise> = Course::children(exercises, folder_path).collect();
+    let first_incomplete = Course::first_incomplete_index(siblings.iter().copied(), &completion);
+
+    siblings
+        .iter()
+        .enumerate()
+        .map(|(index, exercise)| {
+            let locked =
+                parent_locked || (!unblock_all && first_incomplete.is_some_and(|f| index > f));
+            let record = progress.get(&exercise.id);
+
+            if exercise.is_folder {
+                let (completed, total) = Course::folder_progress(exercises, exercise, &completion);
+                ListEntry {
+                    id: exercise.id.clone(),
+                    title: exercise.title.clone(),
+                    kind: "folder",
+                    completed: total > 0 && completed == total,
+                    locked,
+                    last_result: None,
+                    progress: Some(FolderCounts { completed, total }),
+                    children: list_entries(
+                        exercises,
+                        &exercise.path,
+                        progress,
+                        locked,
+                        unblock_all,
+                    ),
+                }
+            } else {
+                ListEntry {
+                    id: exercise.id.clone(),
+                    title: exercise.title.clone(),
+                    kind: "exercise",
+                    completed: record.is_some_and(|p| p.completed),
+                    locked,
+                    last_result: record.and_then(|p| p.last_result.clone()),
+                    progress: None,
+                    children: Vec::new(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn format_entries(entries: &[ListEntry], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    for entry in entries {
+        let locked = if entry.locked { "  [locked]" } else { "" };
+        match &entry.progress {
+            Some(counts) => {
+                let icon = if entry.completed { "✓" } else { "📁" };
+                out.push_str(&format!(
+                    "\n{}{} {} {}/{}{}",
+                    indent, icon, entry.title, counts.completed, counts.total, locked
+                ));
+                format_entries(&entry.children, depth + 1, out);
+            }
+            None => {
+                let icon = if entry.completed {
+                    "✓"
+                } else if entry.last_result.as_deref() == Some("timeout") {
+                    "⏱"
+                } else if entry.last_result.is_some() {
+                    "✗"
+                } else {
+                    " "
+                };
+                out.push_str(&format!(
+                    "\n{}{} {}  ({}){}",
+                    indent, icon, entry.title, entry.id, locked
+                ));
+            }
+        }
+    }
+}
+
+/// `learnp status` output
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    course: String,
+    completed: usize,
+    total: usize,
+    /// First exercise, in course order, that isn't completed yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next: Option<String>,
+    folders: Vec<FolderStatus>,
+    /// Runs recorded for each exercise that has any
+    exercises: Vec<ExerciseAttempts>,
+}
+
+#[derive(Debug, Serialize)]
+struct FolderStatus {
+    id: String,
+    title: String,
+    depth: usize,
+    completed: usize,
+    total: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ExerciseAttempts {
+    id: String,
+    attempts: usize,
+    /// Runs up to and including the first passing one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempts_to_first_pass: Option<usize>,
+    hints_used: usize,
+}
+
+/// `learnp status --exercise ID` output
+#[derive(Debug, Serialize)]
+struct ExerciseHistory {
+    id: String,
+    completed: bool,
+    /// Highest hint level shown: authored hints first, then the AI hint
+    hint_levels_used: usize,
+    attempts: Vec<AttemptEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttemptEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attempted_at: Option<String>,
+    result: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    hint_used: bool,
+    output: String,
+}
+
+impl From<Attempt> for AttemptEntry {
+    fn from(attempt: Attempt) -> Self {
+        Self {
+            attempted_at: attempt.attempted_at.map(|at| at.to_rfc3339()),
+            result: attempt.result,
+            duration_ms: attempt.duration_ms,
+            exit_code: attempt.exit_code,
+            hint_used: attempt.hint_used,
+            output: attempt.output,
+        }
+    }
+}
+
+/// `learnp status [--json] [--exercise ID] [course-directory]`
+///
+/// Prints completed/total for the course and for every folder, or every
+/// recorded run of one exercise.
+pub fn status_command(args: &[String]) -> Result<i32> {
+    const USAGE: &str = "learnp status [--json] [--exercise ID] [course-directory]";
+    let args = CommandArgs::parse(args, &["--json"], &["--exercise"], USAGE)?;
+    if args.positional.len() > 1 {
+        bail!("Usage: {}", USAGE);
+    }
+
+    let course = LoadedCourse::load(args.positional.first().map(String::as_str))?;
+    if let Some(query) = args.value("--exercise") {
+        let exercise = find_exercise(&course.exercises, &course.path, query)?;
+        let history = exercise_history(&course, exercise)?;
+        if args.has("--json") {
+            emit(&serde_json::to_string_pretty(&history)?)?;
+        } else {
+            emit(&format_history(&history))?;
+        }
+        return Ok(EXIT_PASSED);
+    }
+
+    let report = status_report(&course)?;
+
+    if args.has("--json") {
+        emit(&serde_json::to_string_pretty(&report)?)?;
+        return Ok(EXIT_PASSED);
+    }
+
+    let mut text = format!(
+        "{}: {}/{} exercises completed ({}%)",
+        report.course,
+        report.completed,
+        report.total,
+        (report.completed * 100)
+            .checked_div(report.total)
+            .unwrap_or(0)
+    );
+    for folder in &report.folders {
+        text.push_str(&format!(
+            "\n{}{} {}/{}",
+            "  ".repeat(folder.depth + 1),
+            folder.title,
+            folder.completed,
+            folder.total
+        ));
+    }
+    if let Some(next) = &report.next {
+        text.push_str(&format!("\nNext: {}", next));
+    }
+    let runs: usize = report.exercises.iter().map(|e| e.attempts).sum();
+    let first_passes: Vec<usize> = report
+        .exercises
+        .iter()
+        .filter_map(|e| e.attempts_to_first_pass)
+        .collect();
+    if runs > 0 {
+        text.push_str(&format!("\nRuns: {} recorded", runs));
+        if !first_passes.is_empty() {
+            text.push_str(&format!(
+                ", {:.1} per exercise until its first pass",
+                first_passes.iter().sum::<usize>() as f64 / first_passes.len() as f64
+            ));
+        }
+    }
+    emit(&text)?;
+    Ok(EXIT_PASSED)
+}
+
+fn exercise_history(course: &LoadedCourse, exercise: &Exercise) -> Result<ExerciseHistory> {
+    let progress = course.progress()?;
+    let progress = progress.get(&exercise.id);
+    let attempts = course
+        .database
+        .get_attempts(&exercise.id)?
+        .into_iter()
+        .map(AttemptEntry::from)
+        .collect();
+    Ok(ExerciseHistory {
+        id: exercise.id.clone(),
+        completed: progress.is_some_and(|p| p.completed),
+        hint_levels_used: progress.map_or(0, |p| p.hint_levels_used),
+        attempts,
+    })
+}
+
+fn format_history(history: &ExerciseHistory) -> String {
+    let mut text = format!(
+        "{}: {}, {} runs recorded, {} hint levels used",
+        history.id,
+        if history.completed {
+            "completed"
+        } else {
+            "not completed"
+        },
+        history.attempts.len(),
+        history.hint_levels_used
+    );
+    for attempt in &history.attempts {
+        text.push_str(&format!(
+            "\n  {}  {:<7}",
+            attempt.attempted_at.as_deref().unwrap_or("unknown time"),
+            attempt.result
+        ));
+        if let Some(ms) = attempt.duration_ms {
+            text.push_str(&format!("  {:.2}s", ms as f64 / 1000.0));
+        }
+        if let Some(code) = attempt.exit_code {
+            text.push_str(&format!("  exit {}", code));
+        }
+        if attempt.hint_used {
+            text.push_str("  after a hint");
+        }
+    }
+    text
+}
+
+fn status_report(course: &LoadedCourse) -> Result<StatusReport> {
+    let completion = completion_map(&course.progress()?);
+    let exercises_dir = course.exercises_dir();
+
+    let folders = course
+        .exercises
+        .iter()
+        .filter(|e| e.is_folder)
+        .map(|folder| {
+            let (completed, total) =
+                Course::folder_progress(&course.exercises, folder, &completion);
+            let depth = folder
+                .path
+                .strip_prefix(&exercises_dir)
+                .map(|relative| relative.components().count().saturating_sub(1))
+                .unwrap_or(0);
+            FolderStatus {
+                id: folder.id.clone(),
+                title: folder.title.clone(),
+                depth,
+                completed,
+                total,
+            }
+        })
+        .collect();
+
+    let exercises: Vec<&Exercise> = course.exercises.iter().filter(|e| !e.is_folder).collect();
+    let completed = exercises
+        .iter()
+        .filter(|e| completion.get(&e.id).copied().unwrap_or(false))
+        .count();
+    let next = exercises
+        .iter()
+        .find(|e| !completion.get(&e.id).copied().unwrap_or(false))
+        .map(|e| e.id.clone());
+
+    let attempts = course
+        .database
+        .get_attempt_stats()?
+        .into_iter()
+        .map(|stats| ExerciseAttempts {
+            id: stats.exercise_id,
+            attempts: stats.attempts,
+            attempts_to_first_pass: stats.attempts_to_first_pass,
+            hints_used: stats.hints_used,
+        })
+        .collect();
+
+    Ok(StatusReport {
+        course: course.course.name.clone(),
+        completed,
+        total: exercises.len(),
+        next,
+        folders,
+        exercises: attempts,
+    })
+}
+
+/// `learnp run-all [--jobs N] [--folder ID]... [--tag TAG]... [--junit PATH]
+/// [--json-report PATH] [--no-record] [course-directory]`
+///
+/// Runs every selected exercise without the TUI, prints a summary, optionally
+/// writes JUnit XML / JSON reports, and fails unless every exercise passed.
+/// Results are recorded as progress unless `--no-record` is given; failing to
+/// record one only prints a warning.
+pub async fn run_all_command(args: &[String]) -> Result<i32> {
+    const USAGE: &str = "learnp run-all [--jobs N] [--folder ID]... [--tag TAG]... \
+                         [--junit PATH] [--json-report PATH] [--no-record] [course-directory]";
+    let args = CommandArgs::parse(
+        args,
+        &["--no-record"],
+        &["--jobs", "--folder", "--tag", "--junit", "--json-report"],
+        USAGE,
+    )?;
+    if args.positional.len() > 1 {
+        bail!("Usage: {}", USAGE);
+    }
+    let jobs = match args.value("--jobs") {
+        Some(value) => value
+            .parse::<usize>()
+            .ok()
+            .filter(|jobs| *jobs > 0)
+            .with_context(|| format!("--jobs must be a positive number, got '{}'", value))?,
+        None => 1,
+    };
+
+    let course = LoadedCourse::load(args.positional.first().map(String::as_str))?;
+    let selected = select_exercises(
+        &course.exercises,
+        &args.values("--folder"),
+        &args.values("--tag"),
+    )?;
+    if selected.is_empty() {
+        bail!("No exercises match the given filters");
+    }
+
+    println!(
+        "Running {} exercises{}...",
+        selected.len(),
+        if jobs > 1 {
+            format!(" ({} at a time)", jobs)
+        } else {
+            String::new()
+        }
+    );
+
+    let started = Instant::now();
+    let mut outcomes: Vec<Option<TestOutcome>> = vec![None; selected.len()];
+    let mut results_rx = spawn_runs(
+        TestRunner::new(&course.path),
+        selected.iter().map(|e| (*e).clone()).collect(),
+        jobs,
+    );
+    while let Some((index, outcome)) = results_rx.recv().await {
+        println!("{}", outcome_line(selected[index], &outcome));
+        if !args.has("--no-record") {
+            if let Err(e) = course
+                .database
+                .record_outcome(&selected[index].id, &outcome, false)
+            {
+                eprintln!(
+                    "Warning: Failed to record the result of {}: {:#}",
+                    selected[index].id, e
+                );
+            }
+        }
+        outcomes[index] = Some(outcome);
+    }
+    let elapsed = started.elapsed();
+
+    let runs: Vec<(&Exercise, Option<&TestOutcome>)> = selected
+        .iter()
+        .copied()
+        .zip(outcomes.iter().map(Option::as_ref))
+        .collect();
+    let counts = RunCounts::from_runs(&runs);
+
+    // Show what went wrong, since the per-exercise output isn't streamed
+    for (exercise, outcome) in &runs {
+        if let Some(outcome) = outcome {
+            let failed = !matches!(outcome.result, TestResult::Passed | TestResult::Cancelled);
+            if failed && !outcome.output.trim().is_empty() {
+                println!("\n── {} ──\n{}", exercise.id, outcome.output.trim_end());
+            }
+        }
+    }
+
+    println!(
+        "\nTotal: {} | Passed: {} | Failed: {} | Errors: {} | Timeouts: {} | Not run: {} ({:.1}s)",
+        runs.len(),
+        counts.passed,
+        counts.failed,
+        counts.errors,
+        counts.timeouts,
+        counts.not_run,
+        elapsed.as_secs_f64()
+    );
+
+    if let Some(path) = args.value("--junit") {
+        std::fs::write(path, junit_report(&course.course.name, &runs, elapsed))
+            .context(format!("Failed to write JUnit report to {}", path))?;
+    }
+    if let Some(path) = args.value("--json-report") {
+        let report = json_report(&course.course.name, &runs, elapsed);
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)
+            .context(format!("Failed to write JSON report to {}", path))?;
+    }
+
+    Ok(if counts.not_run > 0 {
+        EXIT_CANCELLED
+    } else if counts.passed < runs.len() {
+        EXIT_FAILED
+    } else {
+        EXIT_PASSED
+    })
+}
+
+/// `learnp verify [--folder ID]... [--tag TAG]... [course-directory]`
+///
+/// Checks every selected exercise that has a reference solution: its starter code
+/// must fail the tests and the solution must pass them. Progress is not recorded.
+pub async fn verify_command(args: &[String]) -> Result<i32> {
+    const USAGE: &str = "learnp verify [--folder ID]... [--tag TAG]... [course-directory]";
+    let args = CommandArgs::parse(args, &[], &["--folder", "--tag"], USAGE)?;
+    if args.positional.len() > 1 {
+        bail!("Usage: {}", USAGE);
+    }
+
+    let course = LoadedCourse::load(args.positional.first().map(String::as_str))?;
+    let selected = select_exercises(
+        &course.exercises,
+        &args.values("--folder"),
+        &args.values("--tag"),
+    )?;
+    if selected.is_empty() {
+        bail!("No exercises match the given filters");
+    }
+
+    let test_runner = TestRunner::new(&course.path);
+    let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+    // Tests run in their own process group, so Ctrl+C has to be forwarded
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = cancel_tx.send(()).await;
+        }
+        std::future::pending::<()>().await;
+    });
+
+    println!("Verifying {} exercises...", selected.len());
+    let (mut verified, mut broken, mut missing) = (0, 0, 0);
+    let mut cancelled = false;
+
+    for exercise in &selected {
+        let Some(solution) = exercise.get_solution_dir() else {
+            println!("- {} - no solution", exercise.id);
+            missing += 1;
+            continue;
+        };
+
+        let verification =
+            verify::verify_exercise(&test_runner, exercise, &solution, &mut cancel_rx).await?;
+        if verification.was_cancelled() {
+            cancelled = true;
+            break;
+        }
+
+        let problems = verification.problems();
+        if problems.is_empty() {
+            println!("✓ {}", exercise.id);
+            verified += 1;
+        } else {
+            println!("✗ {} - {}", exercise.id, problems.join("; "));
+            broken += 1;
+        }
+    }
+    ctrl_c.abort();
+
+    println!(
+        "\nVerified: {} | Broken: {} | No solution: {}",
+        verified, broken, missing
+    );
+
+    Ok(if cancelled {
+        EXIT_CANCELLED
+    } else if broken > 0 {
+        EXIT_FAILED
+    } else {
+        EXIT_PASSED
+    })
+}
+
+/// Non-folder exercises inside any of `folders` and tagged with any of `tags`
+/// (directly or through a parent folder). Empty filters select everything.
+fn select_exercises<'a>(
+    exercises: &'a [Exercise],
+    folders: &[&str],
+    tags: &[&str],
+) -> Result<Vec<&'a Exercise>> {
+    let mut folder_paths = Vec::new();
+    for id in folders {
+        let folder = exercises
+            .iter()
+            .find(|e| e.is_folder && e.id == *id)
+            .with_context(|| format!("No folder with id '{}'", id))?;
+        folder_paths.push(folder.path.as_path());
+    }
+
+    let has_tag = |exercise: &Exercise| {
+        exercise
+            .metadata
+            .tags
+            .iter()
+            .flatten()
+            .any(|tag| tags.contains(&tag.as_str()))
+    };
+
+    Ok(exercises
+        .iter()
+        .filter(|e| !e.is_folder)
+        .filter(|e| folder_paths.is_empty() || folder_paths.iter().any(|p| e.path.starts_with(p)))
+        .filter(|e| {
+            tags.is_empty()
+                || exercises
+                    .iter()
+                    .any(|other| e.path.starts_with(&other.path) && has_tag(other))
+        })
+        .collect())
+}
+
+/// Run exercises with at most `jobs` at a time, sending each (index, outcome) as it
+/// finishes. Ctrl+C stops the running tests and skips the rest.
+fn spawn_runs(
+    test_runner: TestRunner,
+    exercises: Vec<Exercise>,
+    jobs: usize,
+) -> mpsc::Receiver<(usize, TestOutcome)> {
+    let (results_tx, results_rx) = mpsc::channel(exercises.len().max(1));
+    let (stop_tx, stop_rx) = watch::channel(false);
+
+    tokio::spawn(async move {
+        // Tests run in their own process groups, so Ctrl+C has to be forwarded
+        let ctrl_c = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = stop_tx.send(true);
+            }
+            // Keep the sender alive so running tests aren't treated as cancelled
+            std::future::pending::<()>().await;
+        });
+
+        let semaphore = Arc::new(Semaphore::new(jobs));
+        let mut tasks = Vec::new();
+
+        for (index, exercise) in exercises.into_iter().enumerate() {
+            let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                break;
+            };
+            if *stop_rx.borrow() {
+                break;
+            }
+
+            let test_runner = test_runner.clone();
+            let results_tx = results_tx.clone();
+            let mut stop_rx = stop_rx.clone();
+
+            tasks.push(tokio::spawn(async move {
+                let _permit = permit;
+                let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
+                let forward_stop = tokio::spawn(async move {
+                    if stop_rx.wait_for(|stopped| *stopped).await.is_ok() {
+                        let _ = cancel_tx.send(()).await;
+                    }
+                    std::future::pending::<()>().await;
+                });
+
+                // Output is kept in the outcome; nobody reads the stream
+                let (output_tx, mut output_rx) = mpsc::channel(100);
+                let drain = tokio::spawn(async move { while output_rx.recv().await.is_some() {} });
+
+                let outcome = match test_runner
+                    .run_test_streaming(&exercise, output_tx, &mut cancel_rx)
+                    .await
+                {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        TestOutcome::new(TestResult::Error(format!("Failed to run test: {:#}", e)))
+                    }
+                };
+                forward_stop.abort();
+                let _ = drain.await;
+                let _ = results_tx.send((index, outcome)).await;
+            }));
+        }
+
+        for task in tasks {
+            let _ = task.await;
+        }
+        ctrl_c.abort();
+    });
+
+    results_rx
+}
+
+#[derive(Debug, Default)]
+struct RunCounts {
+    passed: usize,
+    failed: usize,
+    errors: usize,
+    timeouts: usize,
+    /// Skipped or cancelled after Ctrl+C
+    not_run: usize,
+}
+
+impl RunCounts {
+    fn from_runs(runs: &[(&Exercise, Option<&TestOutcome>)]) -> Self {
+        let mut counts = Self::default();
+        for (_, outcome) in runs {
+            match outcome.map(|o| &o.result) {
+                Some(TestResult::Passed) => counts.passed += 1,
+                Some(TestResult::Failed) => counts.failed += 1,
+                Some(TestResult::Error(_)) => counts.errors += 1,
+                Some(TestResult::Timeout(_)) => counts.timeouts += 1,
+                Some(TestResult::Cancelled) | None => counts.not_run += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// JUnit XML with one test case per exercise, grouped by parent folder
+fn junit_report(
+    course_name: &str,
+    runs: &[(&Exercise, Option<&TestOutcome>)],
+    elapsed: Duration,
+) -> String {
+    let counts = RunCounts::from_runs(runs);
+    let totals = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+        runs.len(),
+        counts.failed,
+        counts.errors + counts.timeouts,
+        counts.not_run,
+        elapsed.as_secs_f64()
+    );
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" {}>\n",
+        xml_escape(course_name),
+        totals
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" {}>\n",
+        xml_escape(course_name),
+        totals
+    ));
+
+    for (exercise, outcome) in runs {
+        let classname = match exercise.id.rsplit_once('/') {
+            Some((parent, _)) => parent,
+            None => course_name,
+        };
+        let time = outcome
+            .and_then(|o| o.duration)
+            .unwrap_or_default()
+            .as_secs_f64();
+        xml.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+            xml_escape(&exercise.id),
+            xml_escape(classname),
+            time
+        ));
+
+        let output = outcome.map(|o| xml_escape(&o.output)).unwrap_or_default();
+        let body = match outcome.map(|o| &o.result) {
+            Some(TestResult::Passed) => None,
+            Some(TestResult::Failed) => Some(format!(
+                "<failure message=\"tests failed\">{}</failure>",
+                output
+            )),
+            Some(TestResult::Error(e)) => Some(format!(
+                "<error message=\"{}\">{}</error>",
+                xml_escape(e),
+                output
+            )),
+            Some(TestResult::Timeout(limit)) => Some(format!(
+                "<error message=\"timed out after {}s\">{}</error>",
+                limit.as_secs(),
+                output
+            )),
+            Some(TestResult::Cancelled) | None => {
+                Some(String::from("<skipped message=\"not run\"/>"))
+            }
+        };
+        match body {
+            Some(body) => xml.push_str(&format!(">\n      {}\n    </testcase>\n", body)),
+            None => xml.push_str("/>\n"),
+        }
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters (e.g. from ANSI colours) are not allowed in XML 1.0
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Serialize)]
+struct RunReport {
+    course: String,
+    duration_ms: u128,
+    passed: usize,
+    failed: usize,
+    errors: usize,
+    timeouts: usize,
+    not_run: usize,
+    exercises: Vec<ExerciseRun>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExerciseRun {
+    id: String,
+    title: String,
+    /// "passed", "failed", "error", "timeout" or "not-run"
+    result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tests: Option<CaseCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    output: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CaseCounts {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+fn json_report(
+    course_name: &str,
+    runs: &[(&Exercise, Option<&TestOutcome>)],
+    elapsed: Duration,
+) -> RunReport {
+    let counts = RunCounts::from_runs(runs);
+    RunReport {
+        course: course_name.to_string(),
+        duration_ms: elapsed.as_millis(),
+        passed: counts.passed,
+        failed: counts.failed,
+        errors: counts.errors,
+        timeouts: counts.timeouts,
+        not_run: counts.not_run,
+        exercises: runs
+            .iter()
+            .map(|(exercise, outcome)| ExerciseRun {
+                id: exercise.id.clone(),
+                title: exercise.title.clone(),
+                result: match outcome.map(|o| &o.result) {
+                    Some(TestResult::Cancelled) | None => "not-run",
+                    Some(result) => result.label(),
+                },
+                duration_ms: outcome.and_then(|o| o.duration).map(|d| d.as_millis()),
+                exit_code: outcome.and_then(|o| o.exit_code),
+                tests: outcome
+                    .and_then(|o| o.summary.as_ref())
+                    .map(|summary| CaseCounts {
+                        passed: summary.passed,
+                        failed: summary.failed,
+                        skipped: summary.skipped,
+                    }),
+                error: match outcome.map(|o| &o.result) {
+                    Some(TestResult::Error(e)) => Some(e.clone()),
+                    _ => None,
+                },
+                output: outcome.map(|o| o.output.clone()).unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+/// Resolve an exercise by ID, by path, or by the last segment of a nested ID
+/// when that is unambiguous
+fn find_exercise<'a>(
+    exercises: &'a [Exercise],
+    course_path: &Path,
+    query: &str,
+) -> Result<&'a Exercise> {
+    let found = match exercises.iter().find(|e| e.id == query) {
+        Some(exercise) => exercise,
+        None => match find_by_path(exercises, course_path, query) {
+            Some(exercise) => exercise,
+            None => {
+                let matches: Vec<&Exercise> = exercises
+                    .iter()
+                    .filter(|e| !e.is_folder && e.id.rsplit('/').next() == Some(query))
+                    .collect();
+                match matches.as_slice() {
+                    [exercise] => *exercise,
+                    [] => bail!("No exercise matches '{}'", query),
+                    _ => bail!(
+                        "'{}' is ambiguous, use one of: {}",
+                        query,
+                        matches
+                            .iter()
+                            .map(|e| e.id.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                }
+            }
+        },
+    };
+
+    if found.is_folder {
+        bail!("'{}' is a folder, not an exercise", found.id);
+    }
+    Ok(found)
+}
+
+fn find_by_path<'a>(
+    exercises: &'a [Exercise],
+    course_path: &Path,
+    query: &str,
+) -> Option<&'a Exercise> {
+    let candidates = [
+        PathBuf::from(query),
+        course_path.join(query),
+        course_path.join("exercises").join(query),
+    ];
+    let target = candidates
+        .iter()
+        .find_map(|candidate| candidate.canonicalize().ok())?;
+
+    exercises
+        .iter()
+        .find(|e| e.path.canonicalize().ok().as_deref() == Some(target.as_path()))
+}
+
+/// One-line summary of a finished run
+fn outcome_line(exercise: &Exercise, outcome: &TestOutcome) -> String {
+    let counts = outcome
+        .summary
+        .as_ref()
+        .map(|summary| format!(" ({})", summary.counts_text()))
+        .unwrap_or_default();
+
+    match &outcome.result {
+        TestResult::Passed => format!("✓ {} - PASSED{}", exercise.title, counts),
+        TestResult::Failed => format!("✗ {} - FAILED{}", exercise.title, counts),
+        TestResult::Error(e) => format!("! {} - ERROR: {}", exercise.title, e),
+        TestResult::Timeout(limit) => {
+            format!("⏱ {} - TIMEOUT after {}s", exercise.title, limit.as_secs())
+        }
+        TestResult::Cancelled => format!("- {} - CANCELLED", exercise.title),
+    }
+}
+
+fn exit_code(result: &TestResult) -> i32 {
+    match result {
+        TestResult::Passed => EXIT_PASSED,
+        TestResult::Failed => EXIT_FAILED,
+        TestResult::Error(_) => EXIT_ERROR,
+        TestResult::Timeout(_) => EXIT_TIMEOUT,
+        TestResult::Cancelled => EXIT_CANCELLED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn load_course(root: &Path) -> Vec<Exercise> {
+        std::fs::write(
+            root.join("course.json"),
+            r#"{"name": "Test", "description": "", "author": "", "version": "1.0"}"#,
+        )
+        .unwrap();
+        for dir in [
+            "001-basics+/001-intro",
+            "001-basics+/002-loops",
+            "001-basics+/003-arrays",
+            "002-advanced+/001-intro",
+        ] {
+            std::fs::create_dir_all(root.join("exercises").join(dir)).unwrap();
+        }
+        Course::load_from_path(root).unwrap().1
+    }
+
+    #[test]
+    fn test_list_and_status() {
+        let temp = TempDir::new().unwrap();
+        let exercises = load_course(temp.path());
+        let database = Database::open(temp.path(), &exercises).unwrap();
+        database.mark_completed("basics/intro").unwrap();
+        database.mark_attempted("basics/loops").unwrap();
+
+        let course = LoadedCourse {
+            course: Course::load_from_path(temp.path()).unwrap().0,
+            path: temp.path().to_path_buf(),
+            exercises,
+            database,
+        };
+        let progress = course.progress().unwrap();
+
+        let entries = list_entries(
+            &course.exercises,
+            &course.exercises_dir(),
+            &progress,
+            false,
+            false,
+        );
+        let basics = &entries[0];
+        assert_eq!(basics.progress.as_ref().unwrap().completed, 1);
+        assert_eq!(basics.children[1].last_result.as_deref(), Some("failed"));
+        // Nothing after the first incomplete exercise in a folder is open
+        let locked: Vec<bool> = basics.children.iter().map(|e| e.locked).collect();
+        assert_eq!(locked, [false, false, true]);
+        assert!(!entries[1].locked);
+        assert!(!entries[1].children[0].locked);
+
+        let report = status_report(&course).unwrap();
+        assert_eq!((report.completed, report.total), (1, 4));
+        assert_eq!(report.next.as_deref(), Some("basics/loops"));
+        assert_eq!(report.folders.len(), 2);
+        assert_eq!(
+            (report.folders[0].completed, report.folders[0].total),
+            (1, 3)
+        );
+        assert!(report.exercises.is_empty());
+
+        for result in [TestResult::Failed, TestResult::Passed] {
+            course
+                .database
+                .record_outcome("basics/loops", &TestOutcome::new(result), true)
+                .unwrap();
+        }
+        let report = status_report(&course).unwrap();
+        assert_eq!(report.exercises.len(), 1);
+        assert_eq!(report.exercises[0].attempts, 2);
+        assert_eq!(report.exercises[0].attempts_to_first_pass, Some(2));
+
+        let loops = find_exercise(&course.exercises, &course.path, "loops").unwrap();
+        course
+            .database
+            .record_hint_level("basics/loops", 2)
+            .unwrap();
+        let history = exercise_history(&course, loops).unwrap();
+        assert!(history.completed);
+        assert_eq!(history.hint_levels_used, 2);
+        let results: Vec<&str> = history.attempts.iter().map(|a| a.result.as_str()).collect();
+        assert_eq!(results, ["failed", "passed"]);
+        assert!(format_history(&history)
+            .starts_with("basics/loops: completed, 2 runs recorded, 2 hint levels used"));
+    }
+
+    #[test]
+    fn test_select_exercises() {
+        let temp = TempDir::new().unwrap();
+        let tagged = temp.path().join("exercises/002-advanced+");
+        std::fs::create_dir_all(&tagged).unwrap();
+        std::fs::write(tagged.join("exercise.json"), r#"{"tags": ["slow"]}"#).unwrap();
+        let exercises = load_course(temp.path());
+
+        let ids = |selected: Vec<&Exercise>| -> Vec<String> {
+            selected.into_iter().map(|e| e.id.clone()).collect()
+        };
+        assert_eq!(select_exercises(&exercises, &[], &[]).unwrap().len(), 4);
+        assert_eq!(
+            ids(select_exercises(&exercises, &["basics"], &[]).unwrap()),
+            ["basics/intro", "basics/loops", "basics/arrays"]
+        );
+        // Folder tags apply to everything inside
+        assert_eq!(
+            ids(select_exercises(&exercises, &[], &["slow"]).unwrap()),
+            ["advanced/intro"]
+        );
+        assert!(select_exercises(&exercises, &["basics"], &["slow"])
+            .unwrap()
+            .is_empty());
+        assert!(select_exercises(&exercises, &["missing"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let temp = TempDir::new().unwrap();
+        let exercises = load_course(temp.path());
+        let failed = TestOutcome {
+            duration: Some(Duration::from_millis(250)),
+            output: String::from("expected <1> & got \u{1b}[31m2\u{1b}[0m"),
+            ..TestOutcome::new(TestResult::Failed)
+        };
+        let passed = TestOutcome::new(TestResult::Passed);
+        let runs = vec![
+            (&exercises[1], Some(&passed)),
+            (&exercises[2], Some(&failed)),
+            (&exercises[3], None),
+        ];
+
+        let xml = junit_report("Test & Co", &runs, Duration::from_secs(1));
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let suite = doc
+            .descendants()
+            .find(|n| n.has_tag_name("testsuite"))
+            .unwrap();
+        assert_eq!(suite.attribute("tests"), Some("3"));
+        assert_eq!(suite.attribute("failures"), Some("1"));
+        assert_eq!(suite.attribute("skipped"), Some("1"));
+
+        let cases: Vec<_> = doc
+            .descendants()
+            .filter(|n| n.has_tag_name("testcase"))
+            .collect();
+        assert_eq!(cases[1].attribute("name"), Some("basics/loops"));
+        assert_eq!(cases[1].attribute("classname"), Some("basics"));
+        let failure = cases[1].first_element_child().unwrap();
+        assert_eq!(failure.text(), Some("expected <1> & got [31m2[0m"));
+        assert!(cases[2]
+            .first_element_child()
+            .unwrap()
+            .has_tag_name("skipped"));
+    }
+
+    #[test]
+    fn test_find_exercise() {
+        let temp = TempDir::new().unwrap();
+        let exercises = load_course(temp.path());
+
+        let by_id = find_exercise(&exercises, temp.path(), "basics/intro").unwrap();
+        assert_eq!(by_id.id, "basics/intro");
+
+        let by_name = find_exercise(&exercises, temp.path(), "loops").unwrap();
+        assert_eq!(by_name.id, "basics/loops");
+
+        let by_path = find_exercise(&exercises, temp.path(), "002-advanced+/001-intro").unwrap();
+        assert_eq!(by_path.id, "advanced/intro");
+
+        let ambiguous = find_exercise(&exercises, temp.path(), "intro").unwrap_err();
+        assert!(ambiguous
+            .to_string()
+            .contains("basics/intro, advanced/intro"));
+
+        assert!(find_exercise(&exercises, temp.path(), "basics").is_err());
+        assert!(find_exercise(&exercises, temp.path(), "missing").is_err());
+    }
+}
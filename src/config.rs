@@ -1,13 +1,41 @@
-use anyhow::{Context, Result};
+use crate::hint::ProviderKind;
+use anyhow::{bail, Context, Result};
 use directories::ProjectDirs;
+use ollama_rs::generation::parameters::KeepAlive;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
+    /// Model used for hints, whichever provider serves it
     pub ollama_model: Option<String>,
+    #[serde(default)]
+    pub hint_provider: ProviderKind,
+    /// Base URL of an OpenAI-compatible API, e.g. `http://localhost:8080/v1`
+    pub openai_base_url: Option<String>,
+    pub openai_api_key: Option<String>,
+    /// Connect/read timeout for the OpenAI-compatible server (`LEARNP_OPENAI_TIMEOUT`)
+    pub openai_timeout_seconds: Option<u64>,
+    /// Ollama server, e.g. `http://localhost:11434` (overridden by `LEARNP_OLLAMA_URL`)
+    pub ollama_url: Option<String>,
+    /// Connect/read timeout for Ollama requests (`LEARNP_OLLAMA_TIMEOUT`)
+    pub ollama_timeout_seconds: Option<u64>,
+    /// Sampling temperature (`LEARNP_OLLAMA_TEMPERATURE`)
+    pub ollama_temperature: Option<f32>,
+    /// Context window in tokens (`LEARNP_OLLAMA_NUM_CTX`)
+    pub ollama_num_ctx: Option<u64>,
+    /// How long the model stays loaded: -1, 0 or e.g. "10m" (`LEARNP_OLLAMA_KEEP_ALIVE`)
+    pub ollama_keep_alive: Option<KeepAlive>,
     pub preferred_editor: Option<String>,
     pub editor_args: Option<Vec<String>>,
+    /// Keys for actions whose defaults should change, e.g. `"hint": ["?"]`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub keybindings: HashMap<String, Vec<String>>,
+    /// Set when config.json exists but couldn't be loaded, so the defaults
+    /// standing in for it are never saved over the user's file
+    #[serde(skip)]
+    pub load_failed: bool,
 }
 
 impl Config {
@@ -25,7 +53,25 @@ impl Config {
         }
     }
 
+    /// The saved config, or the defaults along with the reason it couldn't be
+    /// loaded. Settings changed on the defaults aren't saved.
+    pub fn load_or_default() -> (Self, Option<anyhow::Error>) {
+        match Self::load() {
+            Ok(config) => (config, None),
+            Err(e) => {
+                let config = Config {
+                    load_failed: true,
+                    ..Config::default()
+                };
+                (config, Some(e))
+            }
+        }
+    }
+
     pub fn save(&self) -> Result<()> {
+        if self.load_failed {
+            bail!("config file couldn't be loaded, so it was left untouched");
+        }
         let config_path = Self::get_config_path()?;
 
         // Ensure parent directory exists
@@ -67,3 +113,24 @@ impl Config {
             .map(|editor| (editor, self.editor_args.as_deref().unwrap_or(&[])))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_that_failed_to_load_is_never_saved() {
+        let mut config = Config {
+            load_failed: true,
+            ..Default::default()
+        };
+        assert!(config.set_model("llama3".to_string()).is_err());
+        assert_eq!(config.get_model(), Some("llama3"));
+
+        let config: Config = serde_json::from_str(r#"{"keybindings": {"hint": ["F1"]}}"#).unwrap();
+        assert!(!config.load_failed);
+        assert!(!serde_json::to_string(&config)
+            .unwrap()
+            .contains("load_failed"));
+    }
+}
@@ -1,6 +1,17 @@
+use crate::git::GitRepo;
+use crate::prompt::HINT_PROMPT_FILE;
+use crate::test_report::ReportFormat;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Test time limit used when neither the exercise nor the course sets one
+const DEFAULT_TIMEOUT_SECONDS: u64 = 120;
+
+/// Default location of an exercise's reference solution, relative to the exercise
+const DEFAULT_SOLUTION_DIR: &str = "solution";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Course {
@@ -8,11 +19,24 @@ pub struct Course {
     pub description: String,
     pub author: String,
     pub version: String,
+    /// Default test time limit for exercises that don't set `timeout_seconds`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// Custom hint prompt template, read from `hint_prompt.md` in the course root
+    #[serde(skip)]
+    pub hint_prompt: Option<String>,
 }
 
 /// Optional metadata file for each exercise (exercise.json)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExerciseMetadata {
+    /// Stable progress ID; defaults to the folder path without numeric prefixes,
+    /// e.g. "basics/hello-world"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// IDs this exercise was known by before, so progress survives renames
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_ids: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -21,10 +45,33 @@ pub struct ExerciseMetadata {
     pub test_command: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub setup_command: Option<String>,
+    /// Author-written hints, revealed one level at a time before asking the AI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hints: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub context_files: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub context_patterns: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_report: Option<TestReportConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// Labels for selecting exercises, e.g. with `learnp run-all --tag`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Reference solution overlaid on the exercise by `learnp verify`, relative to
+    /// the exercise directory (defaults to `solution/`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solution_path: Option<String>,
+}
+
+/// Where to find a machine-readable report for an exercise's test run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReportConfig {
+    pub format: ReportFormat,
+    /// Report file relative to the exercise directory; stdout is parsed when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -82,6 +129,32 @@ impl Exercise {
         }
     }
 
+    /// Get the time limit for running this exercise's tests
+    pub fn get_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.metadata
+                .timeout_seconds
+                .unwrap_or(DEFAULT_TIMEOUT_SECONDS)
+                .max(1),
+        )
+    }
+
+    /// Where the reference solution lives, whether or not it exists
+    fn solution_path(&self) -> PathBuf {
+        self.path.join(
+            self.metadata
+                .solution_path
+                .as_deref()
+                .unwrap_or(DEFAULT_SOLUTION_DIR),
+        )
+    }
+
+    /// Get the reference solution directory for this exercise (if any)
+    pub fn get_solution_dir(&self) -> Option<PathBuf> {
+        let dir = self.solution_path();
+        dir.is_dir().then_some(dir)
+    }
+
     /// Get the setup command for this exercise (if any)
     pub fn get_setup_command(&self) -> Option<String> {
         // Use custom setup command if specified
@@ -166,81 +239,41 @@ impl Exercise {
         Ok(context_files)
     }
 
+    /// Discover the exercise's source files, most relevant first: files the
+    /// student changed since the course's git HEAD, then the language's entry
+    /// points, then everything else by path
     fn auto_discover_context_files(
         &self,
         max_files: usize,
         max_file_size: u64,
         max_total_size: u64,
     ) -> Result<Vec<(PathBuf, String)>> {
+        let mut candidates = self.discover_source_files();
+
+        let modified = GitRepo::new(&self.path)
+            .modified_files()
+            .unwrap_or_default();
+        let key_files = self.key_files();
+        candidates.sort_by_cached_key(|path| {
+            let relative = path.strip_prefix(&self.path).unwrap_or(path).to_path_buf();
+            let key_rank = key_files
+                .iter()
+                .position(|key| relative == Path::new(key))
+                .unwrap_or(usize::MAX);
+            (!modified.contains(&relative), key_rank, relative)
+        });
+
         let mut context_files = Vec::new();
         let mut total_size = 0u64;
-
-        // Include key files based on language
-        let key_files = match self.language {
-            Language::JavaScript => vec!["exercise.js", "index.js", "app.js"],
-            Language::Python => vec!["exercise.py", "main.py", "__init__.py"],
-            Language::Rust => vec!["src/main.rs", "src/lib.rs"],
-            Language::Go => vec!["main.go"],
-            Language::Unknown => vec![],
-        };
-
-        for file_name in key_files {
-            let file_path = self.path.join(file_name);
-            if file_path.exists() {
-                if let Ok((path, content)) = Self::read_file_with_limit(&file_path, max_file_size) {
-                    let content_size = content.len() as u64;
-                    if total_size + content_size <= max_total_size {
-                        total_size += content_size;
-                        context_files.push((path, content));
-                    }
-                }
+        for path in candidates {
+            if context_files.len() >= max_files {
+                break;
             }
-        }
-
-        // Walk the exercise directory
-        if let Ok(entries) = std::fs::read_dir(&self.path) {
-            for entry in entries.flatten() {
-                if context_files.len() >= max_files {
-                    break;
-                }
-
-                let path = entry.path();
-                let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-                // Skip excluded files/directories
-                if Self::should_exclude(&path, file_name) {
-                    continue;
-                }
-
-                // If it's a directory, scan it recursively (but only one level deep)
-                if path.is_dir() {
-                    if let Ok(sub_entries) = std::fs::read_dir(&path) {
-                        for sub_entry in sub_entries.flatten() {
-                            if context_files.len() >= max_files {
-                                break;
-                            }
-                            let sub_path = sub_entry.path();
-                            if sub_path.is_file() && Self::is_relevant_file(&sub_path) {
-                                if let Ok((p, content)) =
-                                    Self::read_file_with_limit(&sub_path, max_file_size)
-                                {
-                                    let content_size = content.len() as u64;
-                                    if total_size + content_size <= max_total_size {
-                                        total_size += content_size;
-                                        context_files.push((p, content));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                } else if path.is_file() && Self::is_relevant_file(&path) {
-                    if let Ok((p, content)) = Self::read_file_with_limit(&path, max_file_size) {
-                        let content_size = content.len() as u64;
-                        if total_size + content_size <= max_total_size {
-                            total_size += content_size;
-                            context_files.push((p, content));
-                        }
-                    }
+            if let Ok((path, content)) = Self::read_file_with_limit(&path, max_file_size) {
+                let content_size = content.len() as u64;
+                if total_size + content_size <= max_total_size {
+                    total_size += content_size;
+                    context_files.push((path, content));
                 }
             }
         }
@@ -248,41 +281,119 @@ impl Exercise {
         Ok(context_files)
     }
 
-    fn should_exclude(path: &Path, file_name: &str) -> bool {
-        // Exclude directories
-        if path.is_dir() {
-            return matches!(
-                file_name,
-                "node_modules" | "dist" | "build" | ".cache" | ".git" | "coverage"
-            );
+    /// Entry points worth showing first when the student hasn't changed anything
+    fn key_files(&self) -> &'static [&'static str] {
+        match self.language {
+            Language::JavaScript => &["exercise.js", "index.js", "app.js"],
+            Language::Python => &["exercise.py", "main.py", "__init__.py"],
+            Language::Rust => &["src/main.rs", "src/lib.rs"],
+            Language::Go => &["main.go"],
+            Language::Unknown => &[],
         }
+    }
 
-        // Exclude config and non-source files
+    /// Source files in the exercise, skipping anything `.gitignore`d, build
+    /// output, the reference solution and the exercise's own tests
+    fn discover_source_files(&self) -> Vec<PathBuf> {
+        const MAX_DEPTH: usize = 4;
+
+        let solution = self.solution_path();
+        let walker = ignore::WalkBuilder::new(&self.path)
+            .max_depth(Some(MAX_DEPTH))
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                let name = entry.file_name().to_str().unwrap_or("");
+                let excluded = Self::is_excluded_dir(name) || entry.path() == solution;
+                !(is_dir && entry.depth() > 0 && excluded)
+            })
+            .build();
+
+        walker
+            .flatten()
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                let relative = path.strip_prefix(&self.path).unwrap_or(path);
+                self.is_relevant_file(relative)
+                    && !Self::is_excluded_file(relative)
+                    && !self.is_test_file(relative)
+            })
+            .collect()
+    }
+
+    fn is_excluded_dir(name: &str) -> bool {
+        matches!(
+            name,
+            "node_modules"
+                | "dist"
+                | "build"
+                | "coverage"
+                | "target"
+                | "vendor"
+                | "__pycache__"
+                | "venv"
+                | "playground"
+        )
+    }
+
+    /// Config and lock files that say little about the student's code
+    fn is_excluded_file(path: &Path) -> bool {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
         matches!(
             file_name,
             "package.json"
                 | "package-lock.json"
+                | "pnpm-lock.yaml"
+                | "yarn.lock"
                 | "tsconfig.json"
                 | "jest.config.js"
                 | "webpack.config.js"
                 | "vite.config.js"
-                | ".eslintrc.js"
-                | ".eslintrc.json"
-                | ".prettierrc"
-                | ".gitignore"
-                | "README.md"
-                | "exercise.test.js"
+                | "vitest.config.js"
+                | "exercise.json"
+                | "Cargo.lock"
+                | "go.sum"
         )
     }
 
-    fn is_relevant_file(path: &Path) -> bool {
-        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-            matches!(
-                ext,
-                "js" | "ts" | "jsx" | "tsx" | "css" | "scss" | "html" | "vue" | "json"
-            )
-        } else {
-            false
+    fn is_relevant_file(&self, path: &Path) -> bool {
+        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+            return false;
+        };
+        const WEB: &[&str] = &[
+            "js", "mjs", "cjs", "ts", "jsx", "tsx", "css", "scss", "html", "vue", "json",
+        ];
+        match self.language {
+            Language::JavaScript => WEB.contains(&ext),
+            Language::Python => ext == "py",
+            Language::Rust => ext == "rs",
+            Language::Go => ext == "go",
+            Language::Unknown => WEB.contains(&ext) || matches!(ext, "py" | "rs" | "go"),
+        }
+    }
+
+    /// The exercise's tests, recognised by each language's naming conventions
+    fn is_test_file(&self, path: &Path) -> bool {
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let stem = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+        let in_dir = |dir: &str| path.components().any(|c| c.as_os_str() == dir);
+
+        let javascript = stem.ends_with(".test") || stem.ends_with(".spec") || in_dir("__tests__");
+        let python = file_name.ends_with(".py")
+            && (stem.starts_with("test_")
+                || stem.ends_with("_test")
+                || stem == "conftest"
+                || in_dir("tests"));
+        let rust = file_name.ends_with(".rs") && (in_dir("tests") || in_dir("benches"));
+        let go = file_name.ends_with("_test.go");
+
+        match self.language {
+            Language::JavaScript => javascript,
+            Language::Python => python,
+            Language::Rust => rust,
+            Language::Go => go,
+            Language::Unknown => javascript || python || rust || go,
         }
     }
 
@@ -306,21 +417,57 @@ impl Course {
             course_json_path
         ))?;
 
-        let course: Course =
+        let mut course: Course =
             serde_json::from_str(&course_json).context("Failed to parse course.json")?;
 
+        let hint_prompt_path = course_path.join(HINT_PROMPT_FILE);
+        if hint_prompt_path.exists() {
+            course.hint_prompt = Some(
+                std::fs::read_to_string(&hint_prompt_path)
+                    .context(format!("Failed to read {:?}", hint_prompt_path))?,
+            );
+        }
+
         let exercises_dir = course_path.join("exercises");
         if !exercises_dir.exists() {
             anyhow::bail!("Exercises directory not found at {:?}", exercises_dir);
         }
 
         // Auto-discover exercises from the exercises folder
-        let exercises = Self::load_exercises_from_dir(&exercises_dir, &exercises_dir)?;
+        let exercises = Self::load_exercises_from_dir(&exercises_dir, &course, None)?;
+        Self::check_duplicate_ids(&exercises)?;
 
         Ok((course, exercises))
     }
 
-    fn load_exercises_from_dir(dir: &Path, _root_dir: &Path) -> Result<Vec<Exercise>> {
+    /// Fail if two exercises would share progress, listing every collision
+    fn check_duplicate_ids(exercises: &[Exercise]) -> Result<()> {
+        let mut seen: HashMap<&str, &Path> = HashMap::new();
+        let mut conflicts = Vec::new();
+
+        for exercise in exercises {
+            if let Some(first) = seen.insert(&exercise.id, &exercise.path) {
+                conflicts.push(format!(
+                    "  \"{}\" is used by both {:?} and {:?}",
+                    exercise.id, first, exercise.path
+                ));
+            }
+        }
+
+        if !conflicts.is_empty() {
+            anyhow::bail!(
+                "Duplicate exercise IDs found (set a unique \"id\" in exercise.json):\n{}",
+                conflicts.join("\n")
+            );
+        }
+        Ok(())
+    }
+
+    fn load_exercises_from_dir(
+        dir: &Path,
+        course: &Course,
+        parent_id: Option<&str>,
+    ) -> Result<Vec<Exercise>> {
         let mut exercises = Vec::new();
         let mut entries: Vec<_> = std::fs::read_dir(dir)
             .context(format!("Failed to read directory {:?}", dir))?
@@ -331,7 +478,7 @@ impl Course {
         // Sort entries alphabetically by folder name
         entries.sort_by_key(|a| a.file_name());
 
-        for (_index, entry) in entries.iter().enumerate() {
+        for entry in entries.iter() {
             let exercise_path = entry.path();
             let folder_name = entry.file_name();
             let folder_name_str = folder_name.to_string_lossy();
@@ -339,15 +486,15 @@ impl Course {
             // Check if this is a folder (ends with '+')
             let is_folder = folder_name_str.ends_with('+');
 
-            // Generate ID from folder name (strip leading zeros/numbers and '+' if present)
-            let id = Self::generate_exercise_id(&folder_name_str);
+            // Generate name from folder name (strip leading zeros/numbers and '+' if present)
+            let folder_id = Self::generate_exercise_id(&folder_name_str);
 
             // Detect language
             let language = Exercise::detect_language(&exercise_path);
 
             // Try to load exercise.json metadata
             let metadata_path = exercise_path.join("exercise.json");
-            let metadata: ExerciseMetadata = if metadata_path.exists() {
+            let mut metadata: ExerciseMetadata = if metadata_path.exists() {
                 let metadata_json = std::fs::read_to_string(&metadata_path)
                     .context(format!("Failed to read {:?}", metadata_path))?;
                 serde_json::from_str(&metadata_json)
@@ -356,11 +503,23 @@ impl Course {
                 ExerciseMetadata::default()
             };
 
+            // An explicit id keeps progress stable when the folder is renamed;
+            // otherwise qualify with the parent folders so nested names can't collide
+            let id = metadata.id.clone().unwrap_or_else(|| match parent_id {
+                Some(parent_id) => format!("{}/{}", parent_id, folder_id),
+                None => folder_id.clone(),
+            });
+
+            // Inherit the course-wide time limit
+            if metadata.timeout_seconds.is_none() {
+                metadata.timeout_seconds = course.timeout_seconds;
+            }
+
             // Generate title and description from metadata or folder name
             let title = metadata
                 .title
                 .clone()
-                .unwrap_or_else(|| Self::humanize_name(&id));
+                .unwrap_or_else(|| Self::humanize_name(&folder_id));
             let description = metadata.description.clone().unwrap_or_default();
 
             let readme_file = exercise_path.join("README.md");
@@ -369,7 +528,7 @@ impl Course {
             let order = exercises.len() + 1;
 
             exercises.push(Exercise {
-                id,
+                id: id.clone(),
                 title,
                 description,
                 order,
@@ -382,7 +541,8 @@ impl Course {
 
             // If this is a folder (not an exercise), recursively load its contents
             if is_folder {
-                let sub_exercises = Self::load_exercises_from_dir(&exercise_path, _root_dir)?;
+                let sub_exercises =
+                    Self::load_exercises_from_dir(&exercise_path, course, Some(&id))?;
                 exercises.extend(sub_exercises);
             }
         }
@@ -390,6 +550,93 @@ impl Course {
         Ok(exercises)
     }
 
+    /// Direct children of a folder (or of the exercises root), in course order
+    pub fn children<'a>(
+        exercises: &'a [Exercise],
+        folder_path: &'a Path,
+    ) -> impl Iterator<Item = &'a Exercise> {
+        exercises
+            .iter()
+            .filter(move |ex| ex.path.parent() == Some(folder_path))
+    }
+
+    /// Completed and total exercises anywhere inside a folder
+    pub fn folder_progress(
+        exercises: &[Exercise],
+        folder: &Exercise,
+        progress: &HashMap<String, bool>,
+    ) -> (usize, usize) {
+        if !folder.is_folder {
+            return (0, 0);
+        }
+
+        let mut total = 0;
+        let mut completed = 0;
+
+        for exercise in exercises {
+            if exercise.is_folder {
+                continue; // Skip nested folders, only count exercises
+            }
+
+            // Check if this exercise is a descendant of the folder
+            if exercise.path.starts_with(&folder.path) {
+                total += 1;
+                if progress.get(&exercise.id).copied().unwrap_or(false) {
+                    completed += 1;
+                }
+            }
+        }
+
+        (completed, total)
+    }
+
+    /// Index of the first incomplete exercise among siblings. Siblings after it
+    /// stay locked until it passes.
+    pub fn first_incomplete_index<'a>(
+        siblings: impl IntoIterator<Item = &'a Exercise>,
+        progress: &HashMap<String, bool>,
+    ) -> Option<usize> {
+        siblings.into_iter().position(|exercise| {
+            !exercise.is_folder && !progress.get(&exercise.id).copied().unwrap_or(false)
+        })
+    }
+
+    /// Progress ID renames as (old, new) pairs: those declared through `previous_ids`,
+    /// plus the unqualified folder name that nested exercises used as their ID before
+    /// IDs included the parent folders. Old IDs still used by another exercise are
+    /// skipped so their progress isn't taken over.
+    pub fn id_renames(exercises: &[Exercise]) -> Vec<(String, String)> {
+        let current_ids: HashSet<&str> = exercises.iter().map(|e| e.id.as_str()).collect();
+
+        exercises
+            .iter()
+            .flat_map(|exercise| {
+                let legacy_id = exercise
+                    .metadata
+                    .id
+                    .is_none()
+                    .then(|| {
+                        exercise
+                            .path
+                            .file_name()
+                            .map(|name| Self::generate_exercise_id(&name.to_string_lossy()))
+                    })
+                    .flatten();
+
+                exercise
+                    .metadata
+                    .previous_ids
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .chain(legacy_id)
+                    .filter(|old_id| !current_ids.contains(old_id.as_str()))
+                    .map(|old_id| (old_id, exercise.id.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Generate exercise ID from folder name
     /// Examples: "01-hello-world" -> "hello-world", "hello-world" -> "hello-world", "01-basics+" -> "basics"
     fn generate_exercise_id(folder_name: &str) -> String {
@@ -426,3 +673,191 @@ impl Course {
             .join(" ")
     }
 }
+
+#[cfg(test)]
+impl Exercise {
+    /// A plain exercise in `path` for tests. Set other fields with struct update
+    /// syntax so tests don't need editing when `Exercise` gains a field.
+    pub fn for_test(id: &str, path: &Path) -> Self {
+        Exercise {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: String::new(),
+            order: 1,
+            path: path.to_path_buf(),
+            language: Language::Unknown,
+            metadata: ExerciseMetadata::default(),
+            readme_file: path.join("README.md"),
+            is_folder: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_course(root: &Path) {
+        std::fs::write(
+            root.join("course.json"),
+            r#"{"name": "Test", "description": "", "author": "", "version": "1.0"}"#,
+        )
+        .unwrap();
+    }
+
+    fn write_exercise(root: &Path, relative: &str, metadata: Option<&str>) {
+        let dir = root.join("exercises").join(relative);
+        std::fs::create_dir_all(&dir).unwrap();
+        if let Some(metadata) = metadata {
+            std::fs::write(dir.join("exercise.json"), metadata).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_explicit_id_and_previous_ids() {
+        let temp = TempDir::new().unwrap();
+        write_course(temp.path());
+        write_exercise(
+            temp.path(),
+            "003-let-and-const",
+            Some(r#"{"id": "variables-v2", "previous_ids": ["variables", "hello-world"]}"#),
+        );
+        write_exercise(temp.path(), "001-hello-world", None);
+
+        let (_, exercises) = Course::load_from_path(temp.path()).unwrap();
+        assert_eq!(exercises.len(), 2);
+        assert_eq!(exercises[0].id, "hello-world");
+        assert_eq!(exercises[1].id, "variables-v2");
+        assert_eq!(exercises[1].title, "Let And Const");
+
+        // "hello-world" is still a live exercise, so its progress stays put
+        assert_eq!(
+            Course::id_renames(&exercises),
+            vec![("variables".to_string(), "variables-v2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_nested_ids_are_qualified() {
+        let temp = TempDir::new().unwrap();
+        write_course(temp.path());
+        write_exercise(temp.path(), "001-basics+/001-intro", None);
+        write_exercise(temp.path(), "002-advanced+/001-intro", None);
+
+        let (_, exercises) = Course::load_from_path(temp.path()).unwrap();
+        let ids: Vec<&str> = exercises.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["basics", "basics/intro", "advanced", "advanced/intro"]
+        );
+
+        // Both used to share the "intro" row, so both inherit it
+        assert_eq!(
+            Course::id_renames(&exercises),
+            vec![
+                ("intro".to_string(), "basics/intro".to_string()),
+                ("intro".to_string(), "advanced/intro".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_explicit_ids_are_reported() {
+        let temp = TempDir::new().unwrap();
+        write_course(temp.path());
+        write_exercise(temp.path(), "001-intro", Some(r#"{"id": "intro"}"#));
+        write_exercise(temp.path(), "002-intro-again", Some(r#"{"id": "intro"}"#));
+
+        let err = Course::load_from_path(temp.path()).unwrap_err().to_string();
+        assert!(err.contains("\"intro\" is used by both"));
+        assert!(err.contains("001-intro"));
+        assert!(err.contains("002-intro-again"));
+    }
+
+    fn exercise_at(path: &Path, language: Language) -> Exercise {
+        Exercise {
+            language,
+            ..Exercise::for_test("sum", path)
+        }
+    }
+
+    fn context_names(exercise: &Exercise) -> Vec<String> {
+        exercise
+            .collect_context_files()
+            .unwrap()
+            .into_iter()
+            .map(|(path, _)| {
+                let relative = path.strip_prefix(&exercise.path).unwrap();
+                relative.to_string_lossy().replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_context_discovery_is_language_and_gitignore_aware() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        for file in [
+            "main.py",
+            "helpers/deep/er/util.py",
+            "helpers/deep/er/too/deep.py",
+            "generated.py",
+            "test_main.py",
+            "tests/test_helpers.py",
+            "solution/main.py",
+            "__pycache__/main.py",
+            "notes.js",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "x = 1").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "generated.py\n").unwrap();
+
+        let exercise = exercise_at(root, Language::Python);
+        assert_eq!(
+            context_names(&exercise),
+            ["main.py", "helpers/deep/er/util.py"]
+        );
+    }
+
+    #[test]
+    fn test_context_discovery_puts_modified_files_first() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn a() {}").unwrap();
+        std::fs::write(root.join("src/parse.rs"), "pub fn b() {}").unwrap();
+        std::fs::write(root.join("src/util.rs"), "pub fn c() {}").unwrap();
+
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(root)
+                .output()
+                .is_ok_and(|output| output.status.success())
+        };
+        if !git(&["init", "-q"]) {
+            // git isn't available here
+            return;
+        }
+        assert!(git(&["add", "."]));
+        assert!(git(&[
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-qm",
+            "start",
+        ]));
+        std::fs::write(root.join("src/util.rs"), "pub fn c() { todo!() }").unwrap();
+
+        let exercise = exercise_at(root, Language::Rust);
+        assert_eq!(
+            context_names(&exercise),
+            ["src/util.rs", "src/lib.rs", "src/parse.rs"]
+        );
+    }
+}
@@ -1,9 +1,56 @@
-use anyhow::{Context, Result};
+use crate::course::{Course, Exercise};
+use crate::hint::{ChatMessage, Role};
+use crate::test_runner::{TestOutcome, TestResult};
+use anyhow::{bail, Context, Result};
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+/// A single schema change. `SCHEMA_MIGRATIONS[i]` upgrades a database from
+/// `user_version` i to i + 1.
+struct Migration {
+    description: &'static str,
+    apply: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// Ordered schema history. Only ever append to this list.
+const SCHEMA_MIGRATIONS: &[Migration] = &[
+    Migration {
+        description: "create exercise_progress and id_migrations",
+        apply: migrate_initial_schema,
+    },
+    Migration {
+        description: "add exercise_progress.last_result",
+        apply: migrate_add_last_result,
+    },
+    Migration {
+        description: "create attempts",
+        apply: migrate_add_attempts,
+    },
+    Migration {
+        description: "add exercise_progress.hint_levels_used",
+        apply: migrate_add_hint_levels_used,
+    },
+    Migration {
+        description: "create hint_messages",
+        apply: migrate_add_hint_messages,
+    },
+    Migration {
+        description: "create hint_cache",
+        apply: migrate_add_hint_cache,
+    },
+    Migration {
+        description: "create hint_conversations",
+        apply: migrate_add_hint_conversations,
+    },
+];
+
+const SCHEMA_VERSION: u32 = SCHEMA_MIGRATIONS.len() as u32;
+
+/// Only the tail of a run's output is kept in the attempt history
+const MAX_ATTEMPT_OUTPUT_BYTES: usize = 16 * 1024;
+
 #[derive(Clone)]
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
@@ -17,19 +64,55 @@ pub struct ExerciseProgress {
     pub last_attempt: Option<DateTime<Utc>>,
     #[allow(dead_code)]
     pub completed_at: Option<DateTime<Utc>>,
+    /// Outcome of the most recent run: "passed", "failed" or "timeout"
+    pub last_result: Option<String>,
+    /// Highest hint level shown: authored hints first, then one more for the AI hint
+    pub hint_levels_used: usize,
+}
+
+/// A single recorded test run
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub attempted_at: Option<DateTime<Utc>>,
+    /// "passed", "failed", "error" or "timeout"
+    pub result: String,
+    pub duration_ms: Option<u64>,
+    pub exit_code: Option<i32>,
+    /// Tail of the combined test output
+    pub output: String,
+    /// Whether a hint was shown for the exercise since the previous attempt
+    pub hint_used: bool,
+}
+
+/// How many runs an exercise has taken
+#[derive(Debug, Clone)]
+pub struct AttemptStats {
+    pub exercise_id: String,
+    pub attempts: usize,
+    /// Number of runs up to and including the first passing one
+    pub attempts_to_first_pass: Option<usize>,
+    pub hints_used: usize,
 }
 
 impl Database {
     pub fn new<P: AsRef<Path>>(course_path: P) -> Result<Self> {
         let db_path = Self::get_db_path(course_path)?;
 
-        let conn = Connection::open(&db_path)
+        let existed = db_path.exists();
+
+        let mut conn = Connection::open(&db_path)
             .context(format!("Failed to open database at {:?}", db_path))?;
+        Self::migrate(&mut conn, &db_path, existed)?;
 
-        let db = Database {
+        Ok(Database {
             conn: Arc::new(Mutex::new(conn)),
-        };
-        db.init_schema()?;
+        })
+    }
+
+    /// Open the course database and carry progress over any renamed exercise IDs
+    pub fn open<P: AsRef<Path>>(course_path: P, exercises: &[Exercise]) -> Result<Self> {
+        let db = Self::new(course_path)?;
+        db.migrate_exercise_ids(&Course::id_renames(exercises))?;
         Ok(db)
     }
 
@@ -42,56 +125,429 @@ impl Database {
         Ok(db_path)
     }
 
-    fn init_schema(&self) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS exercise_progress (
-                exercise_id TEXT PRIMARY KEY,
-                completed INTEGER NOT NULL DEFAULT 0,
-                last_attempt TEXT,
-                completed_at TEXT
-            )",
-            [],
-        )?;
+    /// Bring the schema up to `SCHEMA_VERSION`, backing up an existing database first
+    fn migrate(conn: &mut Connection, db_path: &Path, existed: bool) -> Result<()> {
+        let version: u32 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .context("Failed to read database schema version")?;
 
-        // Create migration table to track ID changes
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS id_migrations (
-                old_id TEXT PRIMARY KEY,
-                new_id TEXT NOT NULL,
-                migrated_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+        if version > SCHEMA_VERSION {
+            bail!(
+                "{:?} uses schema version {}, but this learnp only supports up to version {}. \
+                 Please upgrade learnp.",
+                db_path,
+                version,
+                SCHEMA_VERSION
+            );
+        }
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        if existed {
+            let backup_path = db_path.with_extension(format!("db.v{}.bak", version));
+            std::fs::copy(db_path, &backup_path).context(format!(
+                "Failed to back up database to {:?} before migrating",
+                backup_path
+            ))?;
+        }
+
+        for (index, migration) in SCHEMA_MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let target = index as u32 + 1;
+            let tx = conn.transaction()?;
+            (migration.apply)(&tx).context(format!(
+                "Database migration to version {} ({}) failed",
+                target, migration.description
+            ))?;
+            tx.pragma_update(None, "user_version", target)?;
+            tx.commit()?;
+        }
         Ok(())
     }
 
+    /// Mark an exercise as completed, keeping the time of its first completion
     pub fn mark_completed(&self, exercise_id: &str) -> Result<()> {
         let now = Utc::now().to_rfc3339();
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO exercise_progress (exercise_id, completed, last_attempt, completed_at)
-             VALUES (?1, 1, ?2, ?2)",
+            "INSERT INTO exercise_progress (exercise_id, completed, last_attempt, completed_at, last_result)
+             VALUES (?1, 1, ?2, ?2, 'passed')
+             ON CONFLICT(exercise_id) DO UPDATE SET
+                completed = 1,
+                last_attempt = excluded.last_attempt,
+                completed_at = COALESCE(exercise_progress.completed_at, excluded.completed_at),
+                last_result = excluded.last_result",
             params![exercise_id, now],
         )?;
         Ok(())
     }
 
     pub fn mark_attempted(&self, exercise_id: &str) -> Result<()> {
+        self.record_failed_attempt(exercise_id, "failed")
+    }
+
+    /// Record a run that was killed for exceeding the exercise's time limit
+    pub fn mark_timed_out(&self, exercise_id: &str) -> Result<()> {
+        self.record_failed_attempt(exercise_id, "timeout")
+    }
+
+    /// Record an unsuccessful run. A previously completed exercise stays completed.
+    fn record_failed_attempt(&self, exercise_id: &str, result: &str) -> Result<()> {
         let now = Utc::now().to_rfc3339();
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO exercise_progress (exercise_id, completed, last_attempt, completed_at)
-             VALUES (?1, 0, ?2, NULL)",
-            params![exercise_id, now],
+            "INSERT INTO exercise_progress (exercise_id, completed, last_attempt, completed_at, last_result)
+             VALUES (?1, 0, ?2, NULL, ?3)
+             ON CONFLICT(exercise_id) DO UPDATE SET
+                last_attempt = excluded.last_attempt,
+                last_result = excluded.last_result",
+            params![exercise_id, now, result],
+        )?;
+        Ok(())
+    }
+
+    /// Remember that hint `level` was shown. Levels only ever go up.
+    pub fn record_hint_level(&self, exercise_id: &str, level: usize) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO exercise_progress (exercise_id, completed, hint_levels_used)
+             VALUES (?1, 0, ?2)
+             ON CONFLICT(exercise_id) DO UPDATE SET
+                hint_levels_used = MAX(exercise_progress.hint_levels_used, excluded.hint_levels_used)",
+            params![exercise_id, level as i64],
         )?;
         Ok(())
     }
 
+    /// How many hint levels an exercise has revealed so far
+    pub fn get_hint_levels_used(&self, exercise_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let level: Option<i64> = conn
+            .query_row(
+                "SELECT hint_levels_used FROM exercise_progress WHERE exercise_id = ?1",
+                params![exercise_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(level.unwrap_or(0).max(0) as usize)
+    }
+
+    /// The exercise's hint conversation, oldest message first
+    pub fn get_hint_conversation(&self, exercise_id: &str) -> Result<Vec<ChatMessage>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT role, content FROM hint_messages WHERE exercise_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map(params![exercise_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(role, content)| {
+                Role::parse(&role).map(|role| ChatMessage { role, content })
+            })
+            .collect())
+    }
+
+    /// Fingerprint of the code and test output the exercise's hint conversation
+    /// was started for
+    pub fn get_hint_conversation_fingerprint(&self, exercise_id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let fingerprint = conn
+            .query_row(
+                "SELECT fingerprint FROM hint_conversations WHERE exercise_id = ?1",
+                params![exercise_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(fingerprint)
+    }
+
+    /// Replace the exercise's hint conversation with a new one about the code and
+    /// test output identified by `fingerprint`
+    pub fn start_hint_conversation(
+        &self,
+        exercise_id: &str,
+        fingerprint: &str,
+        messages: &[ChatMessage],
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM hint_messages WHERE exercise_id = ?1",
+            params![exercise_id],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO hint_conversations (exercise_id, fingerprint) VALUES (?1, ?2)",
+            params![exercise_id, fingerprint],
+        )?;
+        insert_hint_messages(&tx, exercise_id, messages)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Add follow-up messages to the end of the exercise's hint conversation
+    pub fn append_hint_messages(&self, exercise_id: &str, messages: &[ChatMessage]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        insert_hint_messages(&tx, exercise_id, messages)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// A hint previously generated by `model` for the same code and test output
+    pub fn get_cached_hint(
+        &self,
+        exercise_id: &str,
+        model: &str,
+        fingerprint: &str,
+    ) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let hint = conn
+            .query_row(
+                "SELECT hint FROM hint_cache
+                 WHERE exercise_id = ?1 AND model = ?2 AND fingerprint = ?3",
+                params![exercise_id, model, fingerprint],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(hint)
+    }
+
+    /// Store a generated hint, replacing any earlier one for the same context
+    pub fn cache_hint(
+        &self,
+        exercise_id: &str,
+        model: &str,
+        fingerprint: &str,
+        hint: &str,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO hint_cache (exercise_id, model, fingerprint, hint, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                exercise_id,
+                model,
+                fingerprint,
+                hint,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Update progress for a finished test run and append it to the attempt history.
+    /// Cancelled runs are not recorded at all.
+    pub fn record_outcome(
+        &self,
+        exercise_id: &str,
+        outcome: &TestOutcome,
+        hint_used: bool,
+    ) -> Result<()> {
+        match outcome.result {
+            TestResult::Passed => self.mark_completed(exercise_id)?,
+            TestResult::Failed => self.mark_attempted(exercise_id)?,
+            TestResult::Timeout(_) => self.mark_timed_out(exercise_id)?,
+            TestResult::Error(_) => {}
+            TestResult::Cancelled => return Ok(()),
+        }
+
+        let output = match &outcome.result {
+            // Setup errors never reach the test command, so keep the message instead
+            TestResult::Error(message) if outcome.output.is_empty() => message.as_str(),
+            _ => outcome.output.as_str(),
+        };
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO attempts (exercise_id, attempted_at, result, duration_ms, exit_code, output, hint_used)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                exercise_id,
+                Utc::now().to_rfc3339(),
+                outcome.result.label(),
+                outcome.duration.map(|d| d.as_millis() as i64),
+                outcome.exit_code,
+                truncate_output(output),
+                hint_used,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Move progress and attempt history from old exercise IDs to their new ones,
+    /// merging with any progress already recorded under the new ID. An old ID that
+    /// maps to several new ones is copied to each of them. Each applied rename is
+    /// logged in `id_migrations`. Returns how many renames moved data.
+    pub fn migrate_exercise_ids(&self, renames: &[(String, String)]) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let now = Utc::now().to_rfc3339();
+        let mut applied = 0;
+
+        let mut old_ids: Vec<&str> = Vec::new();
+        for (old_id, new_id) in renames {
+            if old_id == new_id {
+                continue;
+            }
+            if !old_ids.contains(&old_id.as_str()) {
+                old_ids.push(old_id);
+            }
+
+            let merged = tx.execute(
+                "UPDATE exercise_progress AS cur SET
+                    completed = MAX(cur.completed, old.completed),
+                    completed_at = COALESCE(MIN(cur.completed_at, old.completed_at), cur.completed_at, old.completed_at),
+                    last_result = CASE
+                        WHEN cur.last_attempt IS NULL OR old.last_attempt > cur.last_attempt THEN old.last_result
+                        ELSE cur.last_result
+                    END,
+                    last_attempt = COALESCE(MAX(cur.last_attempt, old.last_attempt), cur.last_attempt, old.last_attempt),
+                    hint_levels_used = MAX(cur.hint_levels_used, old.hint_levels_used)
+                 FROM exercise_progress AS old
+                 WHERE cur.exercise_id = ?2 AND old.exercise_id = ?1",
+                params![old_id, new_id],
+            )?;
+            let copied_progress = if merged > 0 {
+                merged
+            } else {
+                tx.execute(
+                    "INSERT INTO exercise_progress (exercise_id, completed, last_attempt, completed_at, last_result, hint_levels_used)
+                     SELECT ?2, completed, last_attempt, completed_at, last_result, hint_levels_used
+                     FROM exercise_progress WHERE exercise_id = ?1",
+                    params![old_id, new_id],
+                )?
+            };
+            // Conversations aren't merged; one already under the new ID wins
+            tx.execute(
+                "INSERT OR REPLACE INTO hint_conversations (exercise_id, fingerprint)
+                 SELECT ?2, fingerprint FROM hint_conversations WHERE exercise_id = ?1
+                   AND NOT EXISTS (SELECT 1 FROM hint_messages WHERE exercise_id = ?2)",
+                params![old_id, new_id],
+            )?;
+            let copied_messages = tx.execute(
+                "INSERT INTO hint_messages (exercise_id, role, content, created_at)
+                 SELECT ?2, role, content, created_at
+                 FROM hint_messages WHERE exercise_id = ?1
+                   AND NOT EXISTS (SELECT 1 FROM hint_messages WHERE exercise_id = ?2)
+                 ORDER BY id",
+                params![old_id, new_id],
+            )?;
+            let copied_hints = tx.execute(
+                "INSERT OR IGNORE INTO hint_cache (exercise_id, model, fingerprint, hint, created_at)
+                 SELECT ?2, model, fingerprint, hint, created_at
+                 FROM hint_cache WHERE exercise_id = ?1",
+                params![old_id, new_id],
+            )?;
+            let copied_attempts = tx.execute(
+                "INSERT INTO attempts (exercise_id, attempted_at, result, duration_ms, exit_code, output, hint_used)
+                 SELECT ?2, attempted_at, result, duration_ms, exit_code, output, hint_used
+                 FROM attempts WHERE exercise_id = ?1 ORDER BY id",
+                params![old_id, new_id],
+            )?;
+
+            if copied_progress + copied_attempts + copied_messages + copied_hints > 0 {
+                tx.execute(
+                    "INSERT OR REPLACE INTO id_migrations (old_id, new_id, migrated_at)
+                     VALUES (?1, ?2, ?3)",
+                    params![old_id, new_id, now],
+                )?;
+                applied += 1;
+            }
+        }
+
+        for old_id in old_ids {
+            tx.execute(
+                "DELETE FROM exercise_progress WHERE exercise_id = ?1",
+                params![old_id],
+            )?;
+            tx.execute(
+                "DELETE FROM attempts WHERE exercise_id = ?1",
+                params![old_id],
+            )?;
+            tx.execute(
+                "DELETE FROM hint_messages WHERE exercise_id = ?1",
+                params![old_id],
+            )?;
+            tx.execute(
+                "DELETE FROM hint_cache WHERE exercise_id = ?1",
+                params![old_id],
+            )?;
+            tx.execute(
+                "DELETE FROM hint_conversations WHERE exercise_id = ?1",
+                params![old_id],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(applied)
+    }
+
+    /// All recorded runs of an exercise, oldest first
+    pub fn get_attempts(&self, exercise_id: &str) -> Result<Vec<Attempt>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT attempted_at, result, duration_ms, exit_code, output, hint_used
+             FROM attempts WHERE exercise_id = ?1 ORDER BY id",
+        )?;
+
+        let attempts = stmt
+            .query_map(params![exercise_id], |row| {
+                Ok(Attempt {
+                    attempted_at: row.get::<_, String>(0)?.parse::<DateTime<Utc>>().ok(),
+                    result: row.get(1)?,
+                    duration_ms: row.get::<_, Option<i64>>(2)?.map(|ms| ms.max(0) as u64),
+                    exit_code: row.get(3)?,
+                    output: row.get(4)?,
+                    hint_used: row.get::<_, i32>(5)? != 0,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(attempts)
+    }
+
+    /// Per-exercise attempt counts, including how many tries the first pass took
+    pub fn get_attempt_stats(&self) -> Result<Vec<AttemptStats>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT exercise_id,
+                    COUNT(*),
+                    (SELECT COUNT(*) FROM attempts earlier
+                     WHERE earlier.exercise_id = a.exercise_id
+                       AND earlier.id <= (SELECT MIN(id) FROM attempts first_pass
+                                          WHERE first_pass.exercise_id = a.exercise_id
+                                            AND first_pass.result = 'passed')),
+                    EXISTS (SELECT 1 FROM attempts p
+                            WHERE p.exercise_id = a.exercise_id AND p.result = 'passed'),
+                    SUM(hint_used)
+             FROM attempts a
+             GROUP BY exercise_id
+             ORDER BY exercise_id",
+        )?;
+
+        let stats = stmt
+            .query_map([], |row| {
+                let passed: bool = row.get(3)?;
+                Ok(AttemptStats {
+                    exercise_id: row.get(0)?,
+                    attempts: row.get::<_, i64>(1)? as usize,
+                    attempts_to_first_pass: passed.then_some(row.get::<_, i64>(2)? as usize),
+                    hints_used: row.get::<_, i64>(4)? as usize,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(stats)
+    }
+
     pub fn get_all_progress(&self) -> Result<Vec<ExerciseProgress>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT exercise_id, completed, last_attempt, completed_at FROM exercise_progress",
+            "SELECT exercise_id, completed, last_attempt, completed_at, last_result, hint_levels_used
+             FROM exercise_progress",
         )?;
 
         let progress_iter = stmt.query_map([], |row| {
@@ -104,6 +560,8 @@ impl Database {
                 completed_at: row
                     .get::<_, Option<String>>(3)?
                     .and_then(|s| s.parse::<DateTime<Utc>>().ok()),
+                last_result: row.get(4)?,
+                hint_levels_used: row.get::<_, i64>(5)?.max(0) as usize,
             })
         })?;
 
@@ -114,3 +572,500 @@ impl Database {
         Ok(results)
     }
 }
+
+// Databases from before versioning have user_version 0 but may already contain
+// some of these tables, so every step tolerates its change being present.
+
+fn migrate_initial_schema(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS exercise_progress (
+            exercise_id TEXT PRIMARY KEY,
+            completed INTEGER NOT NULL DEFAULT 0,
+            last_attempt TEXT,
+            completed_at TEXT
+        )",
+        [],
+    )?;
+
+    // Create migration table to track ID changes
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS id_migrations (
+            old_id TEXT PRIMARY KEY,
+            new_id TEXT NOT NULL,
+            migrated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migrate_add_last_result(conn: &Connection) -> rusqlite::Result<()> {
+    let has_last_result = conn
+        .prepare("SELECT 1 FROM pragma_table_info('exercise_progress') WHERE name = 'last_result'")?
+        .exists([])?;
+    if !has_last_result {
+        conn.execute(
+            "ALTER TABLE exercise_progress ADD COLUMN last_result TEXT",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_add_attempts(conn: &Connection) -> rusqlite::Result<()> {
+    // Every test run, including ones that regress a completed exercise
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS attempts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            exercise_id TEXT NOT NULL,
+            attempted_at TEXT NOT NULL,
+            result TEXT NOT NULL,
+            duration_ms INTEGER,
+            exit_code INTEGER,
+            output TEXT NOT NULL DEFAULT '',
+            hint_used INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS attempts_exercise_id ON attempts (exercise_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migrate_add_hint_levels_used(conn: &Connection) -> rusqlite::Result<()> {
+    let has_column = conn
+        .prepare(
+            "SELECT 1 FROM pragma_table_info('exercise_progress') WHERE name = 'hint_levels_used'",
+        )?
+        .exists([])?;
+    if !has_column {
+        conn.execute(
+            "ALTER TABLE exercise_progress ADD COLUMN hint_levels_used INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_add_hint_messages(conn: &Connection) -> rusqlite::Result<()> {
+    // Hint conversations with the AI tutor, kept across restarts
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS hint_messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            exercise_id TEXT NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS hint_messages_exercise_id ON hint_messages (exercise_id)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migrate_add_hint_cache(conn: &Connection) -> rusqlite::Result<()> {
+    // Generated hints, keyed by the code and test output they were generated for
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS hint_cache (
+            exercise_id TEXT NOT NULL,
+            model TEXT NOT NULL,
+            fingerprint TEXT NOT NULL,
+            hint TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            PRIMARY KEY (exercise_id, model, fingerprint)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn migrate_add_hint_conversations(conn: &Connection) -> rusqlite::Result<()> {
+    // What each saved conversation was started about, so it's only reopened for
+    // the same code and test output
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS hint_conversations (
+            exercise_id TEXT PRIMARY KEY,
+            fingerprint TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn insert_hint_messages(
+    conn: &Connection,
+    exercise_id: &str,
+    messages: &[ChatMessage],
+) -> rusqlite::Result<()> {
+    let now = Utc::now().to_rfc3339();
+    let mut stmt = conn.prepare(
+        "INSERT INTO hint_messages (exercise_id, role, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for message in messages {
+        stmt.execute(params![
+            exercise_id,
+            message.role.as_str(),
+            message.content,
+            now
+        ])?;
+    }
+    Ok(())
+}
+
+/// Keep the end of the output, where failures are usually reported
+fn truncate_output(output: &str) -> String {
+    if output.len() <= MAX_ATTEMPT_OUTPUT_BYTES {
+        return output.to_string();
+    }
+    let mut start = output.len() - MAX_ATTEMPT_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... output truncated ...]\n{}", &output[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn outcome(result: TestResult) -> TestOutcome {
+        TestOutcome {
+            duration: Some(Duration::from_millis(1500)),
+            exit_code: Some(1),
+            output: String::from("1 failing"),
+            ..TestOutcome::new(result)
+        }
+    }
+
+    #[test]
+    fn test_regression_keeps_completion() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        db.record_outcome("intro", &outcome(TestResult::Passed), false)
+            .unwrap();
+        let completed_at = db.get_all_progress().unwrap()[0].completed_at;
+        db.record_outcome("intro", &outcome(TestResult::Failed), false)
+            .unwrap();
+
+        let progress = &db.get_all_progress().unwrap()[0];
+        assert!(progress.completed);
+        assert!(progress.completed_at.is_some());
+        assert_eq!(progress.completed_at, completed_at);
+        assert_eq!(progress.last_result.as_deref(), Some("failed"));
+    }
+
+    #[test]
+    fn test_attempt_history() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        db.record_outcome("intro", &outcome(TestResult::Failed), false)
+            .unwrap();
+        db.record_outcome("intro", &outcome(TestResult::Failed), true)
+            .unwrap();
+        db.record_outcome("intro", &outcome(TestResult::Passed), false)
+            .unwrap();
+        db.record_outcome("intro", &outcome(TestResult::Cancelled), false)
+            .unwrap();
+        db.record_outcome("loops", &outcome(TestResult::Failed), false)
+            .unwrap();
+
+        let attempts = db.get_attempts("intro").unwrap();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].result, "failed");
+        assert_eq!(attempts[0].duration_ms, Some(1500));
+        assert_eq!(attempts[0].exit_code, Some(1));
+        assert_eq!(attempts[0].output, "1 failing");
+        assert!(attempts[1].hint_used);
+
+        let stats = db.get_attempt_stats().unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].exercise_id, "intro");
+        assert_eq!(stats[0].attempts, 3);
+        assert_eq!(stats[0].attempts_to_first_pass, Some(3));
+        assert_eq!(stats[0].hints_used, 1);
+        assert_eq!(stats[1].attempts_to_first_pass, None);
+    }
+
+    #[test]
+    fn test_migrate_exercise_ids() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        db.record_outcome("variables", &outcome(TestResult::Passed), false)
+            .unwrap();
+        db.record_outcome("loops", &outcome(TestResult::Passed), false)
+            .unwrap();
+        // The renamed exercise was already attempted under its new ID
+        db.record_outcome("loops-v2", &outcome(TestResult::Failed), false)
+            .unwrap();
+
+        let renames = vec![
+            ("variables".to_string(), "let-and-const".to_string()),
+            ("loops".to_string(), "loops-v2".to_string()),
+            ("missing".to_string(), "anything".to_string()),
+        ];
+        assert_eq!(db.migrate_exercise_ids(&renames).unwrap(), 2);
+
+        let progress = db.get_all_progress().unwrap();
+        let ids: Vec<&str> = progress.iter().map(|p| p.exercise_id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"let-and-const"));
+        assert!(progress
+            .iter()
+            .all(|p| p.completed && p.completed_at.is_some()));
+        let loops = progress
+            .iter()
+            .find(|p| p.exercise_id == "loops-v2")
+            .unwrap();
+        assert_eq!(loops.last_result.as_deref(), Some("failed"));
+        assert_eq!(db.get_attempts("loops-v2").unwrap().len(), 2);
+
+        // Already applied, so nothing moves the second time
+        assert_eq!(db.migrate_exercise_ids(&renames).unwrap(), 0);
+        let logged: i64 = db
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM id_migrations", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(logged, 2);
+    }
+
+    #[test]
+    fn test_shared_id_is_copied_to_each_new_id() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+        db.record_outcome("intro", &outcome(TestResult::Passed), false)
+            .unwrap();
+
+        let renames = vec![
+            ("intro".to_string(), "basics/intro".to_string()),
+            ("intro".to_string(), "advanced/intro".to_string()),
+        ];
+        assert_eq!(db.migrate_exercise_ids(&renames).unwrap(), 2);
+
+        let mut ids: Vec<String> = db
+            .get_all_progress()
+            .unwrap()
+            .into_iter()
+            .filter(|p| p.completed)
+            .map(|p| p.exercise_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["advanced/intro", "basics/intro"]);
+        assert!(db.get_attempts("intro").unwrap().is_empty());
+        assert_eq!(db.get_attempts("advanced/intro").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_hint_levels_only_go_up_and_survive_renames() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        assert_eq!(db.get_hint_levels_used("intro").unwrap(), 0);
+        db.record_hint_level("intro", 2).unwrap();
+        db.record_hint_level("intro", 1).unwrap();
+        assert_eq!(db.get_hint_levels_used("intro").unwrap(), 2);
+
+        // Test runs leave the hint level alone
+        db.record_outcome("intro", &outcome(TestResult::Failed), true)
+            .unwrap();
+        let progress = &db.get_all_progress().unwrap()[0];
+        assert!(!progress.completed);
+        assert_eq!(progress.hint_levels_used, 2);
+
+        db.migrate_exercise_ids(&[("intro".to_string(), "basics/intro".to_string())])
+            .unwrap();
+        assert_eq!(db.get_hint_levels_used("basics/intro").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_hint_conversation_persists_and_moves_with_renames() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        db.start_hint_conversation(
+            "intro",
+            "abc",
+            &[
+                ChatMessage::user("prompt".to_string()),
+                ChatMessage::assistant("hint".to_string()),
+            ],
+        )
+        .unwrap();
+        db.append_hint_messages(
+            "intro",
+            &[
+                ChatMessage::user("why?".to_string()),
+                ChatMessage::assistant("because".to_string()),
+            ],
+        )
+        .unwrap();
+
+        let reopened = Database::new(temp.path()).unwrap();
+        let conversation = reopened.get_hint_conversation("intro").unwrap();
+        assert_eq!(conversation.len(), 4);
+        assert_eq!(conversation[2], ChatMessage::user("why?".to_string()));
+        assert_eq!(
+            reopened.get_hint_conversation_fingerprint("intro").unwrap(),
+            Some("abc".to_string())
+        );
+
+        // Starting over drops the old conversation
+        db.start_hint_conversation("intro", "def", &[ChatMessage::user("again".to_string())])
+            .unwrap();
+        assert_eq!(db.get_hint_conversation("intro").unwrap().len(), 1);
+
+        assert_eq!(
+            db.migrate_exercise_ids(&[("intro".to_string(), "basics/intro".to_string())])
+                .unwrap(),
+            1
+        );
+        assert!(db.get_hint_conversation("intro").unwrap().is_empty());
+        assert_eq!(db.get_hint_conversation("basics/intro").unwrap().len(), 1);
+        assert_eq!(db.get_hint_conversation_fingerprint("intro").unwrap(), None);
+        assert_eq!(
+            db.get_hint_conversation_fingerprint("basics/intro")
+                .unwrap(),
+            Some("def".to_string())
+        );
+    }
+
+    fn user_version(conn: &Connection) -> u32 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hint_cache_is_keyed_by_model_and_fingerprint() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        db.cache_hint("intro", "qwen", "abc", "first").unwrap();
+        db.cache_hint("intro", "qwen", "abc", "regenerated")
+            .unwrap();
+        assert_eq!(
+            db.get_cached_hint("intro", "qwen", "abc")
+                .unwrap()
+                .as_deref(),
+            Some("regenerated")
+        );
+        assert_eq!(db.get_cached_hint("intro", "llama", "abc").unwrap(), None);
+        assert_eq!(db.get_cached_hint("intro", "qwen", "def").unwrap(), None);
+
+        db.migrate_exercise_ids(&[("intro".to_string(), "basics/intro".to_string())])
+            .unwrap();
+        assert_eq!(db.get_cached_hint("intro", "qwen", "abc").unwrap(), None);
+        assert!(db
+            .get_cached_hint("basics/intro", "qwen", "abc")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_new_database_is_at_latest_version() {
+        let temp = TempDir::new().unwrap();
+        let db = Database::new(temp.path()).unwrap();
+
+        assert_eq!(user_version(&db.conn.lock().unwrap()), SCHEMA_VERSION);
+        // Nothing to back up for a database that did not exist yet
+        let backups = std::fs::read_dir(temp.path())
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .path()
+                    .to_string_lossy()
+                    .ends_with(".bak")
+            })
+            .count();
+        assert_eq!(backups, 0);
+    }
+
+    #[test]
+    fn test_unversioned_database_is_migrated_and_backed_up() {
+        let temp = TempDir::new().unwrap();
+        let db_path = temp.path().join("progress.db");
+        {
+            // Schema as shipped before migrations existed
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE exercise_progress (
+                    exercise_id TEXT PRIMARY KEY,
+                    completed INTEGER NOT NULL DEFAULT 0,
+                    last_attempt TEXT,
+                    completed_at TEXT
+                );
+                CREATE TABLE id_migrations (
+                    old_id TEXT PRIMARY KEY,
+                    new_id TEXT NOT NULL,
+                    migrated_at TEXT NOT NULL
+                );
+                INSERT INTO exercise_progress VALUES
+                    ('intro', 1, '2024-01-01T00:00:00+00:00', '2024-01-01T00:00:00+00:00');",
+            )
+            .unwrap();
+        }
+
+        let db = Database::new(temp.path()).unwrap();
+        assert_eq!(user_version(&db.conn.lock().unwrap()), SCHEMA_VERSION);
+        let progress = &db.get_all_progress().unwrap()[0];
+        assert!(progress.completed);
+        assert_eq!(progress.last_result, None);
+        db.record_outcome("intro", &outcome(TestResult::Failed), false)
+            .unwrap();
+        assert_eq!(db.get_attempts("intro").unwrap().len(), 1);
+
+        let backup = Connection::open(temp.path().join("progress.db.v0.bak")).unwrap();
+        let rows: i64 = backup
+            .query_row("SELECT COUNT(*) FROM exercise_progress", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(user_version(&backup), 0);
+    }
+
+    #[test]
+    fn test_newer_database_is_rejected() {
+        let temp = TempDir::new().unwrap();
+        {
+            let conn = Connection::open(temp.path().join("progress.db")).unwrap();
+            conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+                .unwrap();
+        }
+
+        let err = Database::new(temp.path()).err().unwrap();
+        assert!(err.to_string().contains("upgrade learnp"));
+    }
+
+    #[test]
+    fn test_migrations_tolerate_existing_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in SCHEMA_MIGRATIONS {
+            (migration.apply)(&conn).unwrap();
+        }
+        // Re-running every step against an already current schema is a no-op
+        for migration in SCHEMA_MIGRATIONS {
+            (migration.apply)(&conn).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_truncate_output_keeps_tail() {
+        let output = format!("{}END", "é".repeat(MAX_ATTEMPT_OUTPUT_BYTES));
+        let truncated = truncate_output(&output);
+        assert!(truncated.starts_with("[... output truncated ...]"));
+        assert!(truncated.ends_with("END"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::cell::{Ref, RefCell};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A value built from a file's contents, kept until the file changes. Lets the
+/// UI read and parse a file once instead of on every frame.
+pub struct FileCache<T> {
+    entry: RefCell<Option<Entry<T>>>,
+}
+
+struct Entry<T> {
+    path: PathBuf,
+    modified: SystemTime,
+    len: u64,
+    value: T,
+}
+
+impl<T> FileCache<T> {
+    pub fn new() -> Self {
+        Self {
+            entry: RefCell::new(None),
+        }
+    }
+
+    /// The value for `path`, rebuilt with `build` when the path differs from the
+    /// cached one or the file's modification time or size changed. `None` if the
+    /// file can't be read.
+    pub fn get(&self, path: &Path, build: impl FnOnce(String) -> T) -> Option<Ref<'_, T>> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?;
+        let len = metadata.len();
+
+        let fresh = self.entry.borrow().as_ref().is_some_and(|entry| {
+            entry.path == path && entry.modified == modified && entry.len == len
+        });
+        if !fresh {
+            let entry = std::fs::read_to_string(path).ok().map(|content| Entry {
+                path: path.to_path_buf(),
+                modified,
+                len,
+                value: build(content),
+            });
+            *self.entry.borrow_mut() = entry;
+        }
+
+        Ref::filter_map(self.entry.borrow(), |entry| {
+            entry.as_ref().map(|entry| &entry.value)
+        })
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rebuilds_only_when_file_changes() {
+        let temp = TempDir::new().unwrap();
+        let first = temp.path().join("first.md");
+        let second = temp.path().join("second.md");
+        std::fs::write(&first, "one").unwrap();
+        std::fs::write(&second, "two").unwrap();
+
+        let cache = FileCache::new();
+        let builds = Cell::new(0);
+        let get = |path: &Path| {
+            cache
+                .get(path, |content| {
+                    builds.set(builds.get() + 1);
+                    content.to_uppercase()
+                })
+                .map(|value| value.clone())
+        };
+
+        assert_eq!(get(&first).as_deref(), Some("ONE"));
+        assert_eq!(get(&first).as_deref(), Some("ONE"));
+        assert_eq!(builds.get(), 1);
+
+        assert_eq!(get(&second).as_deref(), Some("TWO"));
+        assert_eq!(builds.get(), 2);
+
+        // Same size, later modification time
+        std::fs::write(&second, "2wo").unwrap();
+        let file = std::fs::File::options().write(true).open(&second).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(get(&second).as_deref(), Some("2WO"));
+        assert_eq!(builds.get(), 3);
+
+        std::fs::remove_file(&second).unwrap();
+        assert_eq!(get(&second), None);
+    }
+}
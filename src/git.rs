@@ -1,9 +1,9 @@
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 pub struct GitRepo {
-    path: std::path::PathBuf,
+    path: PathBuf,
 }
 
 impl GitRepo {
@@ -87,6 +87,21 @@ impl GitRepo {
             .unwrap_or(false)
     }
 
+    /// Files under this directory that differ from HEAD or are untracked,
+    /// relative to it
+    pub fn modified_files(&self) -> Result<Vec<PathBuf>> {
+        let changed =
+            self.run_git_command(&["diff", "--name-only", "--relative", "HEAD", "--", "."])?;
+        let untracked =
+            self.run_git_command(&["ls-files", "--others", "--exclude-standard", "--", "."])?;
+        Ok(changed
+            .lines()
+            .chain(untracked.lines())
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect())
+    }
+
     fn run_git_command(&self, args: &[&str]) -> Result<String> {
         let output = if cfg!(target_os = "windows") {
             Command::new("cmd")
@@ -0,0 +1,201 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+use std::sync::OnceLock;
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{FontStyle, Theme, ThemeSet},
+    parsing::SyntaxSet,
+};
+
+/// Dark theme whose colours sit well next to the TUI's cyan and yellow accents.
+/// Only foreground colours are used, so the terminal background shows through.
+const THEME: &str = "base16-ocean.dark";
+
+/// How many colours the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    TrueColor,
+    Palette256,
+    /// Basic 16-colour terminals get plain code instead of a washed-out theme
+    Basic,
+}
+
+impl ColorSupport {
+    /// Detect from `COLORTERM` and `TERM`
+    pub fn detect(env: impl Fn(&str) -> Option<String>) -> Self {
+        let colorterm = env("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorSupport::TrueColor;
+        }
+        if env("TERM").is_some_and(|term| term.contains("256color")) {
+            return ColorSupport::Palette256;
+        }
+        ColorSupport::Basic
+    }
+}
+
+struct Highlighter {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+    colors: ColorSupport,
+}
+
+fn highlighter() -> &'static Highlighter {
+    static HIGHLIGHTER: OnceLock<Highlighter> = OnceLock::new();
+    HIGHLIGHTER.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        Highlighter {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme: themes.themes.remove(THEME).unwrap_or_default(),
+            colors: ColorSupport::detect(|name| std::env::var(name).ok()),
+        }
+    })
+}
+
+/// Highlight code by its fence language or file extension. Returns `None` when
+/// the language is unknown or the terminal can't show the theme, so callers can
+/// fall back to their plain style.
+pub fn highlight(language: &str, lines: &[String]) -> Option<Vec<Vec<Span<'static>>>> {
+    let highlighter = highlighter();
+    highlight_with(highlighter, highlighter.colors, language, lines)
+}
+
+fn highlight_with(
+    highlighter: &Highlighter,
+    colors: ColorSupport,
+    language: &str,
+    lines: &[String],
+) -> Option<Vec<Vec<Span<'static>>>> {
+    if colors == ColorSupport::Basic || language.is_empty() {
+        return None;
+    }
+    let syntax = highlighter
+        .syntaxes
+        .find_syntax_by_token(language)
+        .or_else(|| highlighter.syntaxes.find_syntax_by_extension(language))?;
+
+    let mut lines_highlighter = HighlightLines::new(syntax, &highlighter.theme);
+    let mut highlighted = Vec::with_capacity(lines.len());
+    for line in lines {
+        // The newline-aware syntaxes need each line to end in one
+        let line = format!("{}\n", line);
+        let regions = lines_highlighter
+            .highlight_line(&line, &highlighter.syntaxes)
+            .ok()?;
+        highlighted.push(
+            regions
+                .into_iter()
+                .map(|(style, text)| {
+                    Span::styled(
+                        text.trim_end_matches('\n').to_string(),
+                        convert_style(style, colors),
+                    )
+                })
+                .filter(|span| !span.content.is_empty())
+                .collect(),
+        );
+    }
+    Some(highlighted)
+}
+
+fn convert_style(style: syntect::highlighting::Style, colors: ColorSupport) -> Style {
+    let color = style.foreground;
+    let fg = match colors {
+        ColorSupport::TrueColor => Color::Rgb(color.r, color.g, color.b),
+        _ => Color::Indexed(rgb_to_256(color.r, color.g, color.b)),
+    };
+
+    let mut converted = Style::default().fg(fg);
+    if style.font_style.contains(FontStyle::BOLD) {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        converted = converted.add_modifier(Modifier::ITALIC);
+    }
+    converted
+}
+
+/// Nearest colour in the xterm 256-colour palette's 6×6×6 cube or grey ramp
+fn rgb_to_256(r: u8, g: u8, b: u8) -> u8 {
+    const STEPS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    let nearest_step = |value: u8| {
+        (0..6)
+            .min_by_key(|&i| (STEPS[i] as i32 - value as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (nearest_step(r), nearest_step(g), nearest_step(b));
+    let cube = 16 + 36 * ri + 6 * gi + bi;
+    let cube_distance = distance((r, g, b), (STEPS[ri], STEPS[gi], STEPS[bi]));
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let grey_index = (average.saturating_sub(8) / 10).min(23) as u8;
+    let grey = 8 + 10 * grey_index;
+    let grey_distance = distance((r, g, b), (grey, grey, grey));
+
+    if grey_distance < cube_distance {
+        232 + grey_index
+    } else {
+        cube as u8
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_color_support_detection() {
+        assert_eq!(
+            ColorSupport::detect(env(&[("COLORTERM", "truecolor"), ("TERM", "xterm")])),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            ColorSupport::detect(env(&[("TERM", "xterm-256color")])),
+            ColorSupport::Palette256
+        );
+        assert_eq!(
+            ColorSupport::detect(env(&[("TERM", "linux")])),
+            ColorSupport::Basic
+        );
+    }
+
+    #[test]
+    fn test_highlight_by_fence_language_and_extension() {
+        let highlighter = highlighter();
+        let code = vec!["fn main() {".to_string(), "}".to_string()];
+
+        let highlighted =
+            highlight_with(highlighter, ColorSupport::TrueColor, "rust", &code).unwrap();
+        assert_eq!(highlighted.len(), 2);
+        let first: String = highlighted[0].iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(first, "fn main() {");
+        assert!(highlighted[0].len() > 1);
+
+        assert!(highlight_with(highlighter, ColorSupport::Palette256, "py", &code).is_some());
+        assert!(highlight_with(highlighter, ColorSupport::TrueColor, "nonsense", &code).is_none());
+        assert!(highlight_with(highlighter, ColorSupport::Basic, "rust", &code).is_none());
+    }
+
+    #[test]
+    fn test_rgb_to_256() {
+        assert_eq!(rgb_to_256(0, 0, 0), 16);
+        assert_eq!(rgb_to_256(255, 255, 255), 231);
+        assert_eq!(rgb_to_256(128, 128, 128), 244);
+        assert_eq!(rgb_to_256(255, 0, 0), 196);
+    }
+}
@@ -0,0 +1,569 @@
+use crate::config::Config;
+use anyhow::{anyhow, bail, Context, Result};
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, BoxStream};
+use futures_util::{FutureExt, StreamExt};
+use ollama_rs::generation::parameters::KeepAlive;
+use ollama_rs::models::ModelOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Tokens of a hint, yielded as the backend produces them
+pub type TokenStream = BoxStream<'static, Result<String>>;
+
+/// Default endpoint of llama.cpp's `llama-server`
+const DEFAULT_OPENAI_BASE_URL: &str = "http://localhost:8080/v1";
+/// Connect/read timeout for the OpenAI-compatible server unless configured
+const DEFAULT_OPENAI_TIMEOUT_SECONDS: u64 = 60;
+
+/// Which backend generates hints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    #[default]
+    Ollama,
+    /// Any server exposing `/v1/chat/completions` (llama.cpp, vLLM, LM Studio, ...)
+    OpenAi,
+}
+
+/// A backend that can list its models and stream a hint for a prompt
+pub trait HintProvider: Send + Sync {
+    /// Human-readable name for status and error messages
+    fn name(&self) -> &str;
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<String>>>;
+
+    /// Stream the model's reply to a conversation
+    fn chat(&self, model: String, messages: Vec<ChatMessage>)
+        -> BoxFuture<'_, Result<TokenStream>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "user" => Some(Role::User),
+            "assistant" => Some(Role::Assistant),
+            _ => None,
+        }
+    }
+}
+
+/// One turn of a hint conversation. The first message is the hint prompt with the
+/// exercise context; later ones are the tutor's answers and the student's follow-ups.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn user(content: String) -> Self {
+        Self {
+            role: Role::User,
+            content,
+        }
+    }
+
+    pub fn assistant(content: String) -> Self {
+        Self {
+            role: Role::Assistant,
+            content,
+        }
+    }
+}
+
+/// Build the provider selected in the user's config
+pub fn provider_from_config(config: &Config) -> Result<Arc<dyn HintProvider>> {
+    Ok(match config.hint_provider {
+        ProviderKind::Ollama => {
+            let settings = OllamaSettings::resolve(config, |name| std::env::var(name).ok())?;
+            Arc::new(OllamaProvider::new(settings)?)
+        }
+        ProviderKind::OpenAi => Arc::new(OpenAiProvider::new(
+            config
+                .openai_base_url
+                .as_deref()
+                .unwrap_or(DEFAULT_OPENAI_BASE_URL),
+            config.openai_api_key.clone(),
+            openai_timeout(config, |name| std::env::var(name).ok())?,
+        )?),
+    })
+}
+
+/// `LEARNP_OPENAI_TIMEOUT`, else `openai_timeout_seconds`, else the default
+fn openai_timeout(config: &Config, env: impl Fn(&str) -> Option<String>) -> Result<Duration> {
+    let seconds = match env("LEARNP_OPENAI_TIMEOUT").filter(|v| !v.trim().is_empty()) {
+        Some(v) => v.trim().parse().with_context(|| {
+            format!(
+                "LEARNP_OPENAI_TIMEOUT must be a number of seconds, got '{}'",
+                v
+            )
+        })?,
+        None => config
+            .openai_timeout_seconds
+            .unwrap_or(DEFAULT_OPENAI_TIMEOUT_SECONDS),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Ollama connection and generation settings: `config.json` values,
+/// overridden by `LEARNP_OLLAMA_*` environment variables
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OllamaSettings {
+    pub url: Option<String>,
+    pub timeout: Option<Duration>,
+    pub temperature: Option<f32>,
+    pub num_ctx: Option<u64>,
+    pub keep_alive: Option<KeepAlive>,
+}
+
+impl OllamaSettings {
+    pub fn resolve(config: &Config, env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| env(name).filter(|v| !v.trim().is_empty());
+
+        let timeout_seconds = match var("LEARNP_OLLAMA_TIMEOUT") {
+            Some(v) => Some(v.trim().parse().with_context(|| {
+                format!(
+                    "LEARNP_OLLAMA_TIMEOUT must be a number of seconds, got '{}'",
+                    v
+                )
+            })?),
+            None => config.ollama_timeout_seconds,
+        };
+        let temperature = match var("LEARNP_OLLAMA_TEMPERATURE") {
+            Some(v) => Some(v.trim().parse().with_context(|| {
+                format!("LEARNP_OLLAMA_TEMPERATURE must be a number, got '{}'", v)
+            })?),
+            None => config.ollama_temperature,
+        };
+        let num_ctx = match var("LEARNP_OLLAMA_NUM_CTX") {
+            Some(v) => Some(v.trim().parse().with_context(|| {
+                format!("LEARNP_OLLAMA_NUM_CTX must be a whole number, got '{}'", v)
+            })?),
+            None => config.ollama_num_ctx,
+        };
+        let keep_alive = match var("LEARNP_OLLAMA_KEEP_ALIVE") {
+            Some(v) => Some(parse_keep_alive(v.trim())?),
+            None => config.ollama_keep_alive.clone(),
+        };
+
+        Ok(Self {
+            url: var("LEARNP_OLLAMA_URL").or_else(|| config.ollama_url.clone()),
+            timeout: timeout_seconds.map(Duration::from_secs),
+            temperature,
+            num_ctx,
+            keep_alive,
+        })
+    }
+}
+
+/// Parse a keep-alive the way Ollama accepts it: `-1`, `0`, or a duration like `10m`
+fn parse_keep_alive(value: &str) -> Result<KeepAlive> {
+    let json = match value.parse::<i64>() {
+        Ok(number) => Value::from(number),
+        Err(_) => Value::from(value),
+    };
+    serde_json::from_value(json).with_context(|| {
+        format!(
+            "Invalid keep-alive '{}': expected -1, 0, or a duration like \"30s\", \"5m\", \"2h\"",
+            value
+        )
+    })
+}
+
+pub struct OllamaProvider {
+    client: ollama_rs::Ollama,
+    settings: OllamaSettings,
+}
+
+impl OllamaProvider {
+    pub fn new(settings: OllamaSettings) -> Result<Self> {
+        let mut builder = reqwest::Client::builder();
+        // A read timeout rather than a total one, so long streamed hints aren't cut off
+        if let Some(timeout) = settings.timeout {
+            builder = builder.connect_timeout(timeout).read_timeout(timeout);
+        }
+        let http = builder.build().context("Failed to create HTTP client")?;
+
+        let mut client = ollama_rs::Ollama::builder().reqwest_client(http);
+        if let Some(url) = &settings.url {
+            let url = reqwest::Url::parse(url)
+                .with_context(|| format!("Invalid Ollama URL '{}'", url))?;
+            client = client.url(url);
+        }
+
+        Ok(Self {
+            client: client.build(),
+            settings,
+        })
+    }
+}
+
+impl HintProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<String>>> {
+        async move {
+            let models = self.client.list_local_models().await?;
+            Ok(models.into_iter().map(|m| m.name).collect())
+        }
+        .boxed()
+    }
+
+    fn chat(
+        &self,
+        model: String,
+        messages: Vec<ChatMessage>,
+    ) -> BoxFuture<'_, Result<TokenStream>> {
+        use ollama_rs::generation::chat::request::ChatMessageRequest;
+        use ollama_rs::generation::chat::ChatMessage as OllamaMessage;
+
+        async move {
+            let messages = messages
+                .into_iter()
+                .map(|message| match message.role {
+                    Role::User => OllamaMessage::user(message.content),
+                    Role::Assistant => OllamaMessage::assistant(message.content),
+                })
+                .collect();
+            let mut request = ChatMessageRequest::new(model, messages);
+            if self.settings.temperature.is_some() || self.settings.num_ctx.is_some() {
+                let mut options = ModelOptions::default();
+                if let Some(temperature) = self.settings.temperature {
+                    options = options.temperature(temperature);
+                }
+                if let Some(num_ctx) = self.settings.num_ctx {
+                    options = options.num_ctx(num_ctx);
+                }
+                request = request.options(options);
+            }
+            if let Some(keep_alive) = self.settings.keep_alive.clone() {
+                request = request.keep_alive(keep_alive);
+            }
+            let responses = self.client.send_chat_messages_stream(request).await?;
+            let tokens = responses.map(|response| {
+                response
+                    .map(|r| r.message.content)
+                    .map_err(|()| anyhow!("Lost the connection to Ollama"))
+            });
+            Ok(tokens.boxed())
+        }
+        .boxed()
+    }
+}
+
+/// Client for OpenAI-compatible `/v1/models` and `/v1/chat/completions` endpoints
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiProvider {
+    pub fn new(base_url: &str, api_key: Option<String>, timeout: Duration) -> Result<Self> {
+        // A read timeout rather than a total one, so long streamed hints aren't cut off
+        let client = reqwest::Client::builder()
+            .connect_timeout(timeout)
+            .read_timeout(timeout)
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self
+            .client
+            .request(method, format!("{}/{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+}
+
+impl HintProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "the OpenAI-compatible server"
+    }
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<String>>> {
+        async move {
+            let response = self
+                .request(reqwest::Method::GET, "models")
+                .send()
+                .await
+                .with_context(|| format!("Failed to reach {}", self.base_url))?;
+            let response = error_for_status(response).await?;
+            let body: Value = response.json().await.context("Invalid model list")?;
+            let models = body["data"]
+                .as_array()
+                .map(|models| {
+                    models
+                        .iter()
+                        .filter_map(|m| m["id"].as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+            Ok(models)
+        }
+        .boxed()
+    }
+
+    fn chat(
+        &self,
+        model: String,
+        messages: Vec<ChatMessage>,
+    ) -> BoxFuture<'_, Result<TokenStream>> {
+        async move {
+            let body = json!({
+                "model": model,
+                "messages": messages,
+                "stream": true,
+            });
+            let response = self
+                .request(reqwest::Method::POST, "chat/completions")
+                .json(&body)
+                .send()
+                .await
+                .with_context(|| format!("Failed to reach {}", self.base_url))?;
+            let response = error_for_status(response).await?;
+            let bytes = response
+                .bytes_stream()
+                .map(|chunk| chunk.map(|b| b.to_vec()).map_err(Into::into));
+            Ok(sse_tokens(bytes.boxed()))
+        }
+        .boxed()
+    }
+}
+
+/// Turn a non-2xx response into an error carrying the server's message
+async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    bail!("Server returned {}: {}", status, body.trim())
+}
+
+struct SseState {
+    bytes: BoxStream<'static, Result<Vec<u8>>>,
+    decoder: SseDecoder,
+    pending: VecDeque<Result<String>>,
+    done: bool,
+}
+
+/// Decode a chat-completions event stream into content tokens
+fn sse_tokens(bytes: BoxStream<'static, Result<Vec<u8>>>) -> TokenStream {
+    let state = SseState {
+        bytes,
+        decoder: SseDecoder::default(),
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                return Some((item, state));
+            }
+            if state.done {
+                return None;
+            }
+            match state.bytes.next().await {
+                Some(Ok(chunk)) => {
+                    for event in state.decoder.push(&chunk) {
+                        match event {
+                            SseEvent::Token(token) => state.pending.push_back(Ok(token)),
+                            SseEvent::Error(message) => {
+                                state.pending.push_back(Err(anyhow!(message)));
+                                state.done = true;
+                            }
+                            SseEvent::Done => state.done = true,
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    state.pending.push_back(Err(e));
+                    state.done = true;
+                }
+                None => state.done = true,
+            }
+        }
+    })
+    .boxed()
+}
+
+#[derive(Debug, PartialEq)]
+enum SseEvent {
+    Token(String),
+    Error(String),
+    Done,
+}
+
+/// Incremental parser for the `data:` lines of a server-sent event stream.
+/// Network chunks can end mid-line, so incomplete lines are buffered.
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDecoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue; // Blank separators, comments and other fields
+            };
+            let data = data.trim();
+
+            if data == "[DONE]" {
+                events.push(SseEvent::Done);
+                continue;
+            }
+            let Ok(value) = serde_json::from_str::<Value>(data) else {
+                continue;
+            };
+            if let Some(error) = value.get("error") {
+                let message = error["message"].as_str().map(String::from);
+                events.push(SseEvent::Error(
+                    message.unwrap_or_else(|| error.to_string()),
+                ));
+            } else if let Some(content) = value["choices"][0]["delta"]["content"].as_str() {
+                if !content.is_empty() {
+                    events.push(SseEvent::Token(content.to_string()));
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ollama_rs::generation::parameters::TimeUnit;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_ollama_settings_env_overrides_config() {
+        let config = Config {
+            ollama_url: Some("http://gpu-box:11434".to_string()),
+            ollama_timeout_seconds: Some(30),
+            ollama_temperature: Some(0.2),
+            ..Default::default()
+        };
+        let env: HashMap<&str, &str> = [
+            ("LEARNP_OLLAMA_URL", "http://localhost:9999"),
+            ("LEARNP_OLLAMA_NUM_CTX", "8192"),
+            ("LEARNP_OLLAMA_KEEP_ALIVE", "10m"),
+        ]
+        .into();
+
+        let settings =
+            OllamaSettings::resolve(&config, |name| env.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(settings.url.as_deref(), Some("http://localhost:9999"));
+        assert_eq!(settings.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(settings.temperature, Some(0.2));
+        assert_eq!(settings.num_ctx, Some(8192));
+        assert_eq!(
+            settings.keep_alive,
+            Some(KeepAlive::Until {
+                time: 10,
+                unit: TimeUnit::Minutes
+            })
+        );
+    }
+
+    #[test]
+    fn test_ollama_settings_rejects_invalid_env_values() {
+        let config = Config::default();
+        let err = OllamaSettings::resolve(&config, |name| {
+            (name == "LEARNP_OLLAMA_TIMEOUT").then(|| "soon".to_string())
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("LEARNP_OLLAMA_TIMEOUT"));
+
+        assert_eq!(parse_keep_alive("-1").unwrap(), KeepAlive::Indefinitely);
+        assert!(parse_keep_alive("forever").is_err());
+    }
+
+    #[test]
+    fn test_openai_timeout_resolution() {
+        let no_env = |_: &str| None;
+        let mut config = Config::default();
+        assert_eq!(
+            openai_timeout(&config, no_env).unwrap(),
+            Duration::from_secs(DEFAULT_OPENAI_TIMEOUT_SECONDS)
+        );
+
+        config.openai_timeout_seconds = Some(15);
+        assert_eq!(
+            openai_timeout(&config, no_env).unwrap(),
+            Duration::from_secs(15)
+        );
+
+        let env = |value: &'static str| move |_: &str| Some(value.to_string());
+        assert_eq!(
+            openai_timeout(&config, env("5")).unwrap(),
+            Duration::from_secs(5)
+        );
+        assert!(openai_timeout(&config, env("soon")).is_err());
+    }
+
+    #[test]
+    fn test_sse_decoder_handles_split_lines() {
+        let mut decoder = SseDecoder::default();
+
+        let events = decoder
+            .push(b"data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\ndata: {\"choi");
+        assert_eq!(events, vec![SseEvent::Token("Hel".to_string())]);
+
+        let events = decoder
+            .push(b"ces\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n: keep-alive\n\ndata: [DONE]\n\n");
+        assert_eq!(
+            events,
+            vec![SseEvent::Token("lo".to_string()), SseEvent::Done]
+        );
+    }
+
+    #[test]
+    fn test_sse_decoder_skips_role_only_deltas_and_reports_errors() {
+        let mut decoder = SseDecoder::default();
+        let events = decoder.push(
+            b"data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\
+              data: {\"error\":{\"message\":\"model not loaded\"}}\n",
+        );
+        assert_eq!(
+            events,
+            vec![SseEvent::Error("model not loaded".to_string())]
+        );
+    }
+}
@@ -0,0 +1,574 @@
+use crate::ui::DisplayMode;
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use DisplayMode::*;
+
+/// Every view the keymap applies to. The search overlay and the follow-up
+/// question box take text input and aren't remappable.
+const ALL_MODES: &[DisplayMode] = &[
+    Readme,
+    ReadmeFocused,
+    TestOutput,
+    Hint,
+    ModelSelection,
+    RunAllTests,
+    PlaygroundConfirm,
+    EditorSelection,
+    Help,
+];
+
+/// Views that scroll a block of text
+const SCROLL_MODES: &[DisplayMode] = &[TestOutput, Hint, RunAllTests, ReadmeFocused, Help];
+
+const PICKER_MODES: &[DisplayMode] = &[ModelSelection, EditorSelection];
+
+/// Something a key can do. Each action only applies in some views, so one key
+/// can mean different things in different views without conflicting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Back,
+    Confirm,
+    Help,
+    SelectNext,
+    SelectPrevious,
+    EnterFolder,
+    LeaveFolder,
+    ReadReadme,
+    OpenEditor,
+    RunAllTests,
+    Search,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    ScrollTop,
+    ScrollBottom,
+    PickerNext,
+    PickerPrevious,
+    Hint,
+    FollowUp,
+    RegenerateHint,
+    ChangeModel,
+    Playground,
+    Yes,
+    No,
+}
+
+impl Action {
+    /// In the order the help overlay lists them
+    pub const ALL: &'static [Action] = &[
+        Action::SelectNext,
+        Action::SelectPrevious,
+        Action::EnterFolder,
+        Action::LeaveFolder,
+        Action::Confirm,
+        Action::ReadReadme,
+        Action::OpenEditor,
+        Action::RunAllTests,
+        Action::Search,
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::PageDown,
+        Action::PageUp,
+        Action::ScrollTop,
+        Action::ScrollBottom,
+        Action::Hint,
+        Action::FollowUp,
+        Action::RegenerateHint,
+        Action::ChangeModel,
+        Action::Playground,
+        Action::PickerNext,
+        Action::PickerPrevious,
+        Action::Yes,
+        Action::No,
+        Action::Help,
+        Action::Back,
+        Action::Quit,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Back => "Back / cancel",
+            Action::Confirm => "Run tests, open folder or confirm selection",
+            Action::Help => "Show or hide this help",
+            Action::SelectNext => "Next exercise",
+            Action::SelectPrevious => "Previous exercise",
+            Action::EnterFolder => "Open folder",
+            Action::LeaveFolder => "Go up a folder",
+            Action::ReadReadme => "Read the exercise full screen",
+            Action::OpenEditor => "Open in editor",
+            Action::RunAllTests => "Run all tests",
+            Action::Search => "Search the course",
+            Action::ScrollDown => "Scroll down",
+            Action::ScrollUp => "Scroll up",
+            Action::PageDown => "Page down",
+            Action::PageUp => "Page up",
+            Action::ScrollTop => "Scroll to top",
+            Action::ScrollBottom => "Scroll to bottom",
+            Action::PickerNext => "Next choice",
+            Action::PickerPrevious => "Previous choice",
+            Action::Hint => "Get a hint, or the next one",
+            Action::FollowUp => "Ask the tutor a follow-up question",
+            Action::RegenerateHint => "Regenerate the AI hint",
+            Action::ChangeModel => "Change hint model",
+            Action::Playground => "Extract to playground",
+            Action::Yes => "Confirm overwrite",
+            Action::No => "Cancel overwrite",
+        }
+    }
+
+    /// The action's name in the config file, e.g. `run_all_tests`
+    pub fn name(self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.as_str().map(String::from))
+            .unwrap_or_default()
+    }
+
+    /// Views the action applies in
+    pub fn modes(self) -> &'static [DisplayMode] {
+        match self {
+            Action::Quit | Action::Back | Action::Help => ALL_MODES,
+            Action::Confirm => &[
+                Readme,
+                ReadmeFocused,
+                TestOutput,
+                Hint,
+                ModelSelection,
+                RunAllTests,
+                PlaygroundConfirm,
+                EditorSelection,
+            ],
+            Action::SelectNext
+            | Action::SelectPrevious
+            | Action::EnterFolder
+            | Action::LeaveFolder
+            | Action::ReadReadme
+            | Action::OpenEditor
+            | Action::RunAllTests => &[Readme],
+            Action::Search => &[Readme, ReadmeFocused],
+            Action::ScrollDown
+            | Action::ScrollUp
+            | Action::PageDown
+            | Action::PageUp
+            | Action::ScrollTop
+            | Action::ScrollBottom => SCROLL_MODES,
+            Action::PickerNext | Action::PickerPrevious => PICKER_MODES,
+            Action::Hint => &[TestOutput, Hint],
+            Action::FollowUp | Action::RegenerateHint => &[Hint],
+            Action::ChangeModel => &[TestOutput, Hint, ModelSelection],
+            Action::Playground => &[TestOutput],
+            Action::Yes | Action::No => &[PlaygroundConfirm],
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Back => &["Esc"],
+            Action::Confirm => &["Enter"],
+            Action::Help => &["?"],
+            Action::SelectNext => &["Down", "j"],
+            Action::SelectPrevious => &["Up", "k"],
+            Action::EnterFolder => &["Right", "l"],
+            Action::LeaveFolder => &["Backspace", "Left", "h"],
+            Action::ReadReadme => &["r"],
+            Action::OpenEditor => &["o"],
+            Action::RunAllTests => &["Shift+A"],
+            Action::Search => &["/"],
+            Action::ScrollDown => &["Down", "j"],
+            Action::ScrollUp => &["Up", "k"],
+            Action::PageDown => &["PageDown", "Ctrl+d"],
+            Action::PageUp => &["PageUp", "Ctrl+u"],
+            Action::ScrollTop => &["Home", "g"],
+            Action::ScrollBottom => &["End", "Shift+G"],
+            Action::PickerNext => &["Down"],
+            Action::PickerPrevious => &["Up"],
+            Action::Hint => &["h"],
+            Action::FollowUp => &["c"],
+            Action::RegenerateHint => &["r"],
+            Action::ChangeModel => &["m"],
+            Action::Playground => &["p"],
+            Action::Yes => &["y"],
+            Action::No => &["n"],
+        }
+    }
+}
+
+/// A key with the modifiers that must be held. Shift is part of the character
+/// for character keys, so `Shift+a` and `A` are the same binding, and
+/// `Shift+Tab` is the back-tab key terminals send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+    /// Only set for named and function keys
+    shift: bool,
+}
+
+impl KeyBinding {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let shift_matches = match self.code {
+            KeyCode::Char(_) | KeyCode::BackTab => true,
+            _ => self.shift == key.modifiers.contains(KeyModifiers::SHIFT),
+        };
+        self.code == key.code
+            && self.ctrl == key.modifiers.contains(KeyModifiers::CONTROL)
+            && self.alt == key.modifiers.contains(KeyModifiers::ALT)
+            && shift_matches
+    }
+}
+
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Backspace", KeyCode::Backspace),
+    ("Tab", KeyCode::Tab),
+    ("Delete", KeyCode::Delete),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Space", KeyCode::Char(' ')),
+];
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+            .with_context(|| {
+                let names: Vec<String> = Action::ALL.iter().map(|a| a.name()).collect();
+                format!(
+                    "Unknown action '{}', expected one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = anyhow::Error;
+
+    /// Parse `q`, `Enter`, `Ctrl+d`, `Shift+A`, `F5` and the like
+    fn from_str(text: &str) -> Result<Self> {
+        let mut parts: Vec<&str> = text.split('+').collect();
+        // "+" itself, or a binding ending in it like "Ctrl++"
+        if text.ends_with("++") || text == "+" {
+            parts.truncate(parts.len().saturating_sub(2));
+            parts.push("+");
+        }
+        let Some((key, modifiers)) = parts.split_last() else {
+            bail!("Empty key binding");
+        };
+
+        let (mut ctrl, mut alt, mut shift) = (false, false, false);
+        for modifier in modifiers {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "alt" => alt = true,
+                "shift" => shift = true,
+                _ => bail!("Unknown modifier '{}' in '{}'", modifier, text),
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ if shift && key.eq_ignore_ascii_case("Tab") => KeyCode::BackTab,
+            _ => {
+                if let Some(number) = key.strip_prefix(['F', 'f']).and_then(|n| n.parse().ok()) {
+                    KeyCode::F(number)
+                } else {
+                    NAMED_KEYS
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                        .map(|(_, code)| *code)
+                        .with_context(|| format!("Unknown key '{}' in '{}'", key, text))?
+                }
+            }
+        };
+        let shift = shift && !matches!(code, KeyCode::Char(_) | KeyCode::BackTab);
+        Ok(KeyBinding {
+            code,
+            ctrl,
+            alt,
+            shift,
+        })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Char(c) if c.is_ascii_uppercase() => write!(f, "Shift+{}", c),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            code => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, named)| *named == code)
+                    .map_or("?", |(name, _)| name);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+/// Which keys trigger which actions
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|key| key.parse().expect("default key bindings parse"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Keymap { bindings }
+    }
+}
+
+impl Keymap {
+    /// The default keymap with the user's bindings replacing those of the
+    /// actions they list. Fails on unknown keys and on two actions sharing a
+    /// key in the same view.
+    pub fn from_config(overrides: &HashMap<String, Vec<String>>) -> Result<Self> {
+        let mut keymap = Keymap::default();
+        for (name, keys) in overrides {
+            let action: Action = name.parse()?;
+            let keys = keys
+                .iter()
+                .map(|key| key.parse())
+                .collect::<Result<Vec<KeyBinding>>>()
+                .with_context(|| format!("Invalid key binding for '{}'", name))?;
+            keymap.bindings.insert(action, keys);
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    fn check_conflicts(&self) -> Result<()> {
+        for &mode in ALL_MODES {
+            let mut seen: Vec<(KeyBinding, Action)> = Vec::new();
+            for &action in Action::ALL.iter().filter(|a| a.modes().contains(&mode)) {
+                for key in self.keys(action) {
+                    if let Some((_, other)) = seen.iter().find(|(seen_key, _)| seen_key == key) {
+                        bail!(
+                            "'{}' is bound to both {} and {} in the {:?} view",
+                            key,
+                            other.name(),
+                            action.name(),
+                            mode
+                        );
+                    }
+                    seen.push((*key, action));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// The action a key press triggers in a view
+    pub fn action(&self, key: &KeyEvent, mode: DisplayMode) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| {
+            action.modes().contains(&mode) && self.keys(*action).iter().any(|k| k.matches(key))
+        })
+    }
+
+    /// The first key bound to an action, for status bar hints
+    pub fn primary(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map_or_else(|| String::from("unbound"), |key| key.to_string())
+    }
+
+    /// Status bar hints for some actions, e.g. "h - hint, Esc - back"
+    pub fn hints(&self, hints: &[(Action, &str)]) -> String {
+        hints
+            .iter()
+            .map(|&(action, label)| format!("{} - {}", self.primary(action), label))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// The scrolling keys as a status bar hint
+    pub fn scroll_hint(&self) -> String {
+        format!(
+            "{}/{} {}/{} {}/{} - scroll",
+            self.primary(Action::ScrollUp),
+            self.primary(Action::ScrollDown),
+            self.primary(Action::PageUp),
+            self.primary(Action::PageDown),
+            self.primary(Action::ScrollTop),
+            self.primary(Action::ScrollBottom),
+        )
+    }
+
+    /// Keys for an action as shown to the user, e.g. "Down / j"
+    pub fn describe(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_and_display_round_trip() {
+        for text in [
+            "q",
+            "Enter",
+            "Ctrl+d",
+            "Shift+A",
+            "PageDown",
+            "F5",
+            "/",
+            "Space",
+            "Alt++",
+            "Shift+Down",
+            "Ctrl+Shift+F5",
+        ] {
+            let key: KeyBinding = text.parse().unwrap();
+            assert_eq!(key.to_string(), text);
+        }
+        assert_eq!(
+            "ctrl+shift+g".parse::<KeyBinding>().unwrap().to_string(),
+            "Ctrl+Shift+G"
+        );
+        assert_eq!(
+            "shift+tab".parse::<KeyBinding>().unwrap().to_string(),
+            "Shift+Tab"
+        );
+        assert!("Hyper+x".parse::<KeyBinding>().is_err());
+        assert!("Banana".parse::<KeyBinding>().is_err());
+    }
+
+    #[test]
+    fn test_default_keymap_matches_by_view() {
+        let keymap = Keymap::default();
+        keymap.check_conflicts().unwrap();
+
+        let h = press(KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&h, Readme), Some(Action::LeaveFolder));
+        assert_eq!(keymap.action(&h, TestOutput), Some(Action::Hint));
+        assert_eq!(keymap.action(&h, ModelSelection), None);
+
+        let shift_a = press(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action(&shift_a, Readme), Some(Action::RunAllTests));
+        let ctrl_d = press(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action(&ctrl_d, Hint), Some(Action::PageDown));
+        let d = press(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&d, Hint), None);
+    }
+
+    #[test]
+    fn test_shift_on_named_keys_is_part_of_the_binding() {
+        let shift_down: KeyBinding = "Shift+Down".parse().unwrap();
+        let down: KeyBinding = "Down".parse().unwrap();
+        assert_ne!(shift_down, down);
+        assert!(shift_down.matches(&press(KeyCode::Down, KeyModifiers::SHIFT)));
+        assert!(!shift_down.matches(&press(KeyCode::Down, KeyModifiers::NONE)));
+        assert!(!down.matches(&press(KeyCode::Down, KeyModifiers::SHIFT)));
+
+        let back_tab: KeyBinding = "Shift+Tab".parse().unwrap();
+        assert!(back_tab.matches(&press(KeyCode::BackTab, KeyModifiers::SHIFT)));
+        assert!(back_tab.matches(&press(KeyCode::BackTab, KeyModifiers::NONE)));
+        assert!(!back_tab.matches(&press(KeyCode::Tab, KeyModifiers::NONE)));
+
+        let overrides =
+            HashMap::from([("scroll_bottom".to_string(), vec!["Shift+Down".to_string()])]);
+        let keymap = Keymap::from_config(&overrides).unwrap();
+        let shift_down = press(KeyCode::Down, KeyModifiers::SHIFT);
+        let down = press(KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(keymap.action(&shift_down, Hint), Some(Action::ScrollBottom));
+        assert_eq!(keymap.action(&down, Hint), Some(Action::ScrollDown));
+    }
+
+    #[test]
+    fn test_overrides_replace_defaults_and_conflicts_are_rejected() {
+        let overrides = HashMap::from([("hint".to_string(), vec!["?".to_string()])]);
+        let error = Keymap::from_config(&overrides).unwrap_err();
+        assert!(error.to_string().contains("bound to both"), "{}", error);
+
+        let overrides = HashMap::from([
+            ("hint".to_string(), vec!["F1".to_string()]),
+            (
+                "quit".to_string(),
+                vec!["Ctrl+c".to_string(), "q".to_string()],
+            ),
+        ]);
+        let keymap = Keymap::from_config(&overrides).unwrap();
+        let f1 = press(KeyCode::F(1), KeyModifiers::NONE);
+        let h = press(KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(&f1, TestOutput), Some(Action::Hint));
+        assert_eq!(keymap.action(&h, TestOutput), None);
+        assert_eq!(keymap.describe(Action::Quit), "Ctrl+c / q");
+        assert_eq!(
+            keymap.hints(&[(Action::Hint, "hint"), (Action::Back, "back")]),
+            "F1 - hint, Esc - back"
+        );
+
+        let overrides = HashMap::from([("quit".to_string(), vec!["Ctrl+Nope".to_string()])]);
+        assert!(Keymap::from_config(&overrides).is_err());
+
+        let overrides = HashMap::from([("hnt".to_string(), vec!["F2".to_string()])]);
+        let error = Keymap::from_config(&overrides).unwrap_err();
+        assert!(
+            error.to_string().contains("Unknown action 'hnt'"),
+            "{}",
+            error
+        );
+    }
+}
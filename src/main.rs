@@ -1,11 +1,22 @@
+mod cli;
 mod config;
 mod course;
 mod database;
 mod editor;
+mod file_cache;
 mod git;
+mod highlight;
+mod hint;
+mod keymap;
+mod markdown;
 mod playground;
+mod progress;
+mod prompt;
+mod search;
+mod test_report;
 mod test_runner;
 mod ui;
+mod verify;
 
 use anyhow::{Context, Result};
 use std::env;
@@ -63,16 +74,59 @@ fn handle_config_command(args: &[String]) -> Result<()> {
     }
 }
 
+/// Print a command's error and exit with the code it asked for
+fn exit_with(result: Result<i32>) -> ! {
+    match result {
+        Ok(code) => std::process::exit(code),
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(cli::EXIT_ERROR);
+        }
+    }
+}
+
 fn print_help(program_name: &str) {
     println!("learnp - Interactive TUI application for learning programming through exercises");
     println!();
     println!("USAGE:");
     println!("    {} [OPTIONS] [course-directory]", program_name);
     println!("    {} config [--path]", program_name);
+    println!("    {} test <exercise> [course-directory]", program_name);
+    println!(
+        "    {} list [--json] [--unblock-all] [course-directory]",
+        program_name
+    );
+    println!(
+        "    {} status [--json] [--exercise ID] [course-directory]",
+        program_name
+    );
+    println!(
+        "    {} run-all [--jobs N] [--folder ID]... [--tag TAG]... [--junit PATH] [--json-report PATH] [--no-record] [course-directory]",
+        program_name
+    );
+    println!(
+        "    {} verify [--folder ID]... [--tag TAG]... [course-directory]",
+        program_name
+    );
     println!();
     println!("COMMANDS:");
     println!("    config               Open configuration file in your editor");
     println!("    config --path        Print the path to the configuration file");
+    println!("    test <exercise>      Run one exercise's tests and record the result");
+    println!("                         (exercise id, path, or unambiguous name)");
+    println!("    list                 Show the course tree with completion and lock state");
+    println!("    status               Show completed/total exercises per folder");
+    println!("      --exercise ID      Show every recorded run of one exercise instead");
+    println!("    --json               Print list or status as JSON");
+    println!("    run-all              Run every exercise's tests without the TUI (for CI)");
+    println!("      --jobs N           Run up to N exercises at once (default 1)");
+    println!("      --folder ID        Only run exercises inside this folder (repeatable)");
+    println!("      --tag TAG          Only run exercises with this tag (repeatable)");
+    println!("      --junit PATH       Write a JUnit XML report");
+    println!("      --json-report PATH Write a JSON report");
+    println!("      --no-record        Don't record the results as progress");
+    println!("    verify               Check that each exercise's starter code fails its tests");
+    println!("                         and its reference solution passes (for course authors)");
     println!();
     println!("OPTIONS:");
     println!("    -h, --help           Print help information");
@@ -89,6 +143,9 @@ fn print_help(program_name: &str) {
     println!();
     println!("    You can also use the LEARNP_UNBLOCK_ALL=1 environment variable to unlock all exercises.");
     println!();
+    println!("EXIT STATUS (test, run-all):");
+    println!("    0 passed, 1 failed, 2 error, 124 timed out (test only), 130 interrupted");
+    println!();
     println!("EXAMPLES:");
     println!("    {} ./my-course", program_name);
     println!("    {}              # Uses current directory", program_name);
@@ -101,6 +158,7 @@ fn print_help(program_name: &str) {
         "    {} config --path # Print config file path",
         program_name
     );
+    println!("    {} test basics/hello-world ./my-course", program_name);
 }
 
 #[tokio::main]
@@ -112,6 +170,18 @@ async fn main() -> Result<()> {
         return handle_config_command(&args);
     }
 
+    // Non-interactive commands
+    if args.len() >= 2 {
+        match args[1].as_str() {
+            "test" => exit_with(cli::test_command(&args[2..]).await),
+            "list" => exit_with(cli::list_command(&args[2..])),
+            "run-all" => exit_with(cli::run_all_command(&args[2..]).await),
+            "verify" => exit_with(cli::verify_command(&args[2..]).await),
+            "status" => exit_with(cli::status_command(&args[2..])),
+            _ => {}
+        }
+    }
+
     // Parse flags and arguments for normal TUI mode
     let mut unblock_all = false;
     let mut course_path_arg: Option<String> = None;
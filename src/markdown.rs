@@ -0,0 +1,498 @@
+use crate::highlight;
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Render Markdown as styled terminal lines
+pub fn render(markdown: &str) -> Vec<Line<'static>> {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut renderer = Renderer::default();
+    for event in Parser::new_ext(markdown, options) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+struct CodeBlock {
+    language: String,
+    code: String,
+}
+
+#[derive(Default)]
+struct Table {
+    alignments: Vec<Alignment>,
+    rows: Vec<Vec<String>>,
+    in_head: bool,
+    has_head: bool,
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    /// Spans of the line being built
+    spans: Vec<Span<'static>>,
+    /// Inline styles, innermost last
+    styles: Vec<Style>,
+    /// Open lists with the next item number for ordered ones
+    lists: Vec<Option<u64>>,
+    /// Marker for the first line of the current list item
+    item_marker: Option<String>,
+    quote_depth: usize,
+    code_block: Option<CodeBlock>,
+    table: Option<Table>,
+    /// Target of the link being rendered
+    link: Option<String>,
+}
+
+impl Renderer {
+    fn event(&mut self, event: Event) {
+        if let Some(block) = &mut self.code_block {
+            match event {
+                Event::Text(text) => block.code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => self.end_code_block(),
+                _ => {}
+            }
+            return;
+        }
+        if let Some(table) = &mut self.table {
+            match event {
+                Event::Start(Tag::TableHead) => {
+                    table.in_head = true;
+                    table.has_head = true;
+                    table.rows.push(Vec::new());
+                }
+                Event::End(TagEnd::TableHead) => table.in_head = false,
+                Event::Start(Tag::TableRow) => table.rows.push(Vec::new()),
+                Event::Start(Tag::TableCell) => {
+                    if let Some(row) = table.rows.last_mut() {
+                        row.push(String::new());
+                    }
+                }
+                Event::Text(text) | Event::Code(text) => {
+                    if let Some(cell) = table.rows.last_mut().and_then(|row| row.last_mut()) {
+                        cell.push_str(&text);
+                    }
+                }
+                Event::End(TagEnd::Table) => self.end_table(),
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.push_text(&text),
+            Event::Code(code) => {
+                let style = self.style().fg(Color::Yellow);
+                self.spans.push(Span::styled(code.to_string(), style));
+            }
+            Event::SoftBreak => self.push_text(" "),
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.lines.push(Line::from(Span::styled(
+                    "─".repeat(40),
+                    Style::default().fg(Color::DarkGray),
+                )));
+                self.blank();
+            }
+            Event::TaskListMarker(done) => {
+                let marker = if done { "☑ " } else { "☐ " };
+                self.spans.push(Span::raw(marker));
+            }
+            // Raw HTML, footnotes and math have no terminal rendering
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush();
+                let style = match level {
+                    HeadingLevel::H1 => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                    _ => Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                };
+                self.styles.push(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth += 1;
+                self.styles.push(
+                    Style::default()
+                        .fg(Color::Gray)
+                        .add_modifier(Modifier::ITALIC),
+                );
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split([' ', ',']).next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code_block = Some(CodeBlock {
+                    language,
+                    code: String::new(),
+                });
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => String::from("• "),
+                };
+                self.item_marker = Some(marker);
+            }
+            Tag::Table(alignments) => {
+                self.flush();
+                self.table = Some(Table {
+                    alignments,
+                    ..Default::default()
+                });
+            }
+            Tag::Emphasis => self.push_style(Modifier::ITALIC),
+            Tag::Strong => self.push_style(Modifier::BOLD),
+            Tag::Strikethrough => self.push_style(Modifier::CROSSED_OUT),
+            Tag::Link { dest_url, .. } => {
+                self.link = Some(dest_url.to_string());
+                let style = self
+                    .style()
+                    .fg(Color::Blue)
+                    .add_modifier(Modifier::UNDERLINED);
+                self.styles.push(style);
+            }
+            Tag::Image { .. } => {
+                let style = self.style().fg(Color::DarkGray);
+                self.spans.push(Span::styled("[image: ", style));
+                self.styles.push(style);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush();
+                self.blank();
+            }
+            TagEnd::Paragraph => {
+                self.flush();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.styles.pop();
+                self.quote_depth -= 1;
+                self.blank();
+            }
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Item => self.flush(),
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                if let Some(url) = self.link.take() {
+                    // Show where the link goes unless the text already does
+                    let text: String = self.spans.iter().map(|s| s.content.as_ref()).collect();
+                    if !text.ends_with(&url) {
+                        self.spans.push(Span::styled(
+                            format!(" ({})", url),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                }
+            }
+            TagEnd::Image => {
+                let style = self.style();
+                self.styles.pop();
+                self.spans.push(Span::styled("]", style));
+            }
+            _ => {}
+        }
+    }
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, modifier: Modifier) {
+        let style = self.style().add_modifier(modifier);
+        self.styles.push(style);
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let style = self.style();
+        self.spans.push(Span::styled(text.to_string(), style));
+    }
+
+    /// Indentation and quote bars in front of every line of the current block
+    fn prefix(&mut self) -> Vec<Span<'static>> {
+        let mut prefix = Vec::new();
+        if self.quote_depth > 0 {
+            prefix.push(Span::styled(
+                "│ ".repeat(self.quote_depth),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if !self.lists.is_empty() {
+            let indent = "  ".repeat(self.lists.len() - 1);
+            match self.item_marker.take() {
+                Some(marker) => {
+                    prefix.push(Span::raw(indent));
+                    prefix.push(Span::styled(marker, Style::default().fg(Color::Cyan)));
+                }
+                None => prefix.push(Span::raw(format!("{}  ", indent))),
+            }
+        }
+        prefix
+    }
+
+    /// Finish the line being built
+    fn flush(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+        let mut spans = self.prefix();
+        spans.append(&mut self.spans);
+        self.lines.push(Line::from(spans));
+    }
+
+    /// Separate blocks with a single empty line
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::from(""));
+        }
+    }
+
+    fn end_code_block(&mut self) {
+        let Some(block) = self.code_block.take() else {
+            return;
+        };
+        let mut prefix = self.prefix();
+        prefix.retain(|span| span.width() > 0);
+        for line in code_block_lines(&block.language, &block.code) {
+            let mut spans = prefix.clone();
+            spans.extend(line.spans);
+            self.lines.push(Line::from(spans));
+        }
+        if self.lists.is_empty() {
+            self.blank();
+        }
+    }
+
+    fn end_table(&mut self) {
+        let Some(table) = self.table.take() else {
+            return;
+        };
+        self.lines.extend(table_lines(&table));
+        self.blank();
+    }
+
+    fn finish(mut self) -> Vec<Line<'static>> {
+        self.flush();
+        while self.lines.last().is_some_and(|line| line.width() == 0) {
+            self.lines.pop();
+        }
+        self.lines
+    }
+}
+
+/// Draw a code block inside a border labelled with its language, highlighted
+/// when the terminal supports it
+fn code_block_lines(language: &str, code: &str) -> Vec<Line<'static>> {
+    let border = Style::default().fg(Color::DarkGray);
+    let code_lines: Vec<String> = code
+        .trim_end_matches('\n')
+        .lines()
+        .map(|line| line.replace('\t', "    "))
+        .collect();
+    let width = code_lines
+        .iter()
+        .map(|line| Span::raw(line.as_str()).width())
+        .max()
+        .unwrap_or(0)
+        .max(language.len() + 2);
+
+    let label = if language.is_empty() {
+        String::new()
+    } else {
+        format!(" {} ", language)
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "┌─{}{}",
+            label,
+            "─".repeat((width + 1).saturating_sub(label.len()))
+        ),
+        border,
+    ))];
+    let highlighted = highlight::highlight(language, &code_lines);
+    for (index, line) in code_lines.into_iter().enumerate() {
+        let mut spans = vec![Span::styled("│ ", border)];
+        match highlighted.as_ref().and_then(|h| h.get(index)) {
+            Some(highlighted) => spans.extend(highlighted.iter().cloned()),
+            None => spans.push(Span::styled(line, Style::default().fg(Color::Green))),
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(Span::styled(
+        format!("└{}", "─".repeat(width + 2)),
+        border,
+    )));
+    lines
+}
+
+/// Draw a table with box-drawing borders, padding cells to their column width
+fn table_lines(table: &Table) -> Vec<Line<'static>> {
+    let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            table
+                .rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| Span::raw(cell.as_str()).width())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let border = Style::default().fg(Color::DarkGray);
+    let rule = |left: &str, middle: &str, right: &str| {
+        let segments: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+        Line::from(Span::styled(
+            format!("{}{}{}", left, segments.join(middle), right),
+            border,
+        ))
+    };
+
+    let mut lines = vec![rule("┌", "┬", "┐")];
+    for (index, row) in table.rows.iter().enumerate() {
+        let is_head = table.has_head && index == 0;
+        let mut spans = vec![Span::styled("│", border)];
+        for (column, width) in widths.iter().enumerate() {
+            let cell = row.get(column).map(String::as_str).unwrap_or("");
+            let padding = width - Span::raw(cell).width();
+            let (left, right) = match table.alignments.get(column) {
+                Some(Alignment::Right) => (padding, 0),
+                Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                _ => (0, padding),
+            };
+            let style = if is_head {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            spans.push(Span::raw(format!(" {}", " ".repeat(left))));
+            spans.push(Span::styled(cell.to_string(), style));
+            spans.push(Span::raw(format!("{} ", " ".repeat(right))));
+            spans.push(Span::styled("│", border));
+        }
+        lines.push(Line::from(spans));
+        if is_head {
+            lines.push(rule("├", "┼", "┤"));
+        }
+    }
+    lines.push(rule("└", "┴", "┘"));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_blocks_are_rendered_without_markdown_syntax() {
+        let lines = render(
+            "# Title\n\nSome **bold** and `code`.\n\n- one\n- two\n  1. nested\n\n> quoted\n\n```js\nlet x = 1;\n```\n",
+        );
+        assert_eq!(
+            plain(&lines),
+            [
+                "Title",
+                "",
+                "Some bold and code.",
+                "",
+                "• one",
+                "• two",
+                "  1. nested",
+                "",
+                "│ quoted",
+                "",
+                "┌─ js ───────",
+                "│ let x = 1;",
+                "└────────────",
+            ]
+        );
+        assert!(lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+        assert!(lines[2].spans[1]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_tables_are_aligned() {
+        let lines = render("| Key | Action |\n|-----|-------:|\n| `h` | Hint |\n");
+        assert_eq!(
+            plain(&lines),
+            [
+                "┌─────┬────────┐",
+                "│ Key │ Action │",
+                "├─────┼────────┤",
+                "│ h   │   Hint │",
+                "└─────┴────────┘",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_links_show_their_target() {
+        let lines = render("See [the docs](https://example.com) or <https://example.com>.");
+        assert_eq!(
+            plain(&lines),
+            ["See the docs (https://example.com) or https://example.com."]
+        );
+    }
+}
@@ -138,6 +138,7 @@ mod tests {
             language: crate::course::Language::JavaScript,
             metadata: Default::default(),
             readme_file: exercise_path.join("README.md"),
+            is_folder: false,
         };
 
         // Extract to playground
@@ -173,6 +174,7 @@ mod tests {
             language: crate::course::Language::JavaScript,
             metadata: Default::default(),
             readme_file: exercise_path.join("README.md"),
+            is_folder: false,
         };
 
         // Should not exist initially
@@ -201,6 +203,7 @@ mod tests {
             language: crate::course::Language::JavaScript,
             metadata: Default::default(),
             readme_file: exercise_path.join("README.md"),
+            is_folder: false,
         };
 
         assert!(playground_exists(&exercise));
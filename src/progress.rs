@@ -0,0 +1,210 @@
+use crate::course::{Course, Exercise};
+use crate::database::Database;
+use crate::test_runner::TestResult;
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Completion state the exercise list needs for one exercise
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExerciseStatus {
+    pub completed: bool,
+    /// The most recent run hit the time limit
+    pub timed_out: bool,
+}
+
+/// In-memory copy of the progress table, so drawing and navigating the list
+/// never queries SQLite. Loaded once and kept in step with recorded test results.
+pub struct ProgressCache {
+    statuses: HashMap<String, ExerciseStatus>,
+    /// Completion flags in the shape `Course`'s progress helpers take
+    completed: HashMap<String, bool>,
+    /// (completed, total) exercises under each folder
+    folder_totals: HashMap<PathBuf, (usize, usize)>,
+    /// Exercises and folders that come after the first incomplete exercise
+    /// among their siblings, or sit inside a folder that does
+    locked: HashSet<PathBuf>,
+}
+
+impl ProgressCache {
+    pub fn load(database: &Database, exercises: &[Exercise]) -> Result<Self> {
+        let statuses = database
+            .get_all_progress()?
+            .into_iter()
+            .map(|p| {
+                let status = ExerciseStatus {
+                    completed: p.completed,
+                    timed_out: p.last_result.as_deref() == Some("timeout"),
+                };
+                (p.exercise_id, status)
+            })
+            .collect();
+
+        let mut cache = Self {
+            statuses,
+            completed: HashMap::new(),
+            folder_totals: HashMap::new(),
+            locked: HashSet::new(),
+        };
+        cache.recompute(exercises);
+        Ok(cache)
+    }
+
+    pub fn status(&self, exercise_id: &str) -> ExerciseStatus {
+        self.statuses.get(exercise_id).copied().unwrap_or_default()
+    }
+
+    /// (completed, total) exercises under a folder
+    pub fn folder_progress(&self, folder: &Exercise) -> (usize, usize) {
+        self.folder_totals
+            .get(&folder.path)
+            .copied()
+            .unwrap_or((0, 0))
+    }
+
+    /// Whether an exercise or folder is locked anywhere in the course
+    pub fn is_locked(&self, exercise: &Exercise) -> bool {
+        self.locked.contains(&exercise.path)
+    }
+
+    /// Index of the first exercise in `siblings` that isn't completed
+    pub fn first_incomplete_index<'a>(
+        &self,
+        siblings: impl IntoIterator<Item = &'a Exercise>,
+    ) -> Option<usize> {
+        Course::first_incomplete_index(siblings, &self.completed)
+    }
+
+    /// Mirror what `Database::record_outcome` stored for a finished run
+    pub fn record(&mut self, exercise_id: &str, result: &TestResult, exercises: &[Exercise]) {
+        let status = self.statuses.entry(exercise_id.to_string()).or_default();
+        match result {
+            TestResult::Passed => {
+                status.completed = true;
+                status.timed_out = false;
+            }
+            TestResult::Failed => status.timed_out = false,
+            TestResult::Timeout(_) => status.timed_out = true,
+            TestResult::Error(_) | TestResult::Cancelled => return,
+        }
+        self.recompute(exercises);
+    }
+
+    fn recompute(&mut self, exercises: &[Exercise]) {
+        self.completed = self
+            .statuses
+            .iter()
+            .map(|(id, status)| (id.clone(), status.completed))
+            .collect();
+        self.folder_totals = exercises
+            .iter()
+            .filter(|exercise| exercise.is_folder)
+            .map(|folder| {
+                let totals = Course::folder_progress(exercises, folder, &self.completed);
+                (folder.path.clone(), totals)
+            })
+            .collect();
+
+        let mut siblings: HashMap<&Path, Vec<&Exercise>> = HashMap::new();
+        for exercise in exercises {
+            if let Some(parent) = exercise.path.parent() {
+                siblings.entry(parent).or_default().push(exercise);
+            }
+        }
+        let mut locked_here = HashSet::new();
+        for group in siblings.values() {
+            if let Some(first) =
+                Course::first_incomplete_index(group.iter().copied(), &self.completed)
+            {
+                locked_here.extend(group[first + 1..].iter().map(|e| e.path.as_path()));
+            }
+        }
+        self.locked = exercises
+            .iter()
+            .filter(|exercise| {
+                exercise
+                    .path
+                    .ancestors()
+                    .any(|path| locked_here.contains(path))
+            })
+            .map(|exercise| exercise.path.clone())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_runner::TestOutcome;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn write_exercise(root: &std::path::Path, relative: &str) {
+        std::fs::create_dir_all(root.join("exercises").join(relative)).unwrap();
+    }
+
+    #[test]
+    fn test_cache_tracks_recorded_results() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join("course.json"),
+            r#"{"name": "Test", "description": "", "author": "", "version": "1.0"}"#,
+        )
+        .unwrap();
+        write_exercise(temp.path(), "001-basics+/001-intro");
+        write_exercise(temp.path(), "001-basics+/002-loops");
+        write_exercise(temp.path(), "001-basics+/003-nested+/001-deep");
+        let (_, exercises) = Course::load_from_path(temp.path()).unwrap();
+        let folder = exercises.iter().find(|e| e.is_folder).unwrap().clone();
+        let path_of = |id: &str| exercises.iter().find(|e| e.id == id).unwrap().clone();
+        let (loops, nested, deep) = (
+            path_of("basics/loops"),
+            path_of("basics/nested"),
+            path_of("basics/nested/deep"),
+        );
+
+        let db_dir = TempDir::new().unwrap();
+        let database = Database::new(db_dir.path()).unwrap();
+        database
+            .record_outcome("basics/intro", &TestOutcome::new(TestResult::Passed), false)
+            .unwrap();
+
+        let mut cache = ProgressCache::load(&database, &exercises).unwrap();
+        assert!(cache.status("basics/intro").completed);
+        assert_eq!(cache.folder_progress(&folder), (1, 3));
+        assert!(!cache.is_locked(&loops));
+        // Locked after the first incomplete sibling, and so is everything inside
+        assert!(cache.is_locked(&nested));
+        assert!(cache.is_locked(&deep));
+        let children: Vec<&Exercise> = Course::children(&exercises, &folder.path).collect();
+        assert_eq!(
+            cache.first_incomplete_index(children.iter().copied()),
+            Some(1)
+        );
+
+        cache.record(
+            "basics/loops",
+            &TestResult::Timeout(Duration::from_secs(1)),
+            &exercises,
+        );
+        assert!(cache.status("basics/loops").timed_out);
+        assert_eq!(cache.folder_progress(&folder), (1, 3));
+
+        cache.record("basics/loops", &TestResult::Passed, &exercises);
+        assert_eq!(
+            cache.status("basics/loops"),
+            ExerciseStatus {
+                completed: true,
+                timed_out: false
+            }
+        );
+        assert_eq!(cache.folder_progress(&folder), (2, 3));
+        assert_eq!(cache.first_incomplete_index(children), None);
+        assert!(!cache.is_locked(&nested));
+        assert!(!cache.is_locked(&deep));
+
+        // A later failure doesn't take completion away
+        cache.record("basics/loops", &TestResult::Failed, &exercises);
+        assert!(cache.status("basics/loops").completed);
+    }
+}
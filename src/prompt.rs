@@ -0,0 +1,315 @@
+use crate::course::{Exercise, Language};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// File in the course root that replaces the default hint prompt
+pub const HINT_PROMPT_FILE: &str = "hint_prompt.md";
+
+/// Default hint prompt. Course templates can use the same placeholders:
+/// `{{title}}`, `{{description}}`, `{{language}}`, `{{files}}` and `{{test_output}}`.
+const DEFAULT_HINT_PROMPT: &str = r#"You are a helpful programming tutor. A student is working on the following {{language}} exercise:
+
+Exercise: {{title}}
+Description: {{description}}
+
+{{files}}
+
+Test output showing failures:
+```
+{{test_output}}
+```
+
+Provide a helpful hint (not the full solution) to guide them toward fixing the issue. Be encouraging and educational.
+
+Hint:"#;
+
+/// Everything a hint prompt is built from
+pub struct HintContext<'a> {
+    pub exercise: &'a Exercise,
+    pub files: &'a [(PathBuf, String)],
+    pub test_output: &'a str,
+}
+
+impl HintContext<'_> {
+    /// Hash of the code and test output, identifying hints generated for them.
+    /// Timings are masked so re-running unchanged code keeps the fingerprint.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (path, content) in self.files {
+            let name = path.strip_prefix(&self.exercise.path).unwrap_or(path);
+            hasher.update(name.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(content.as_bytes());
+            hasher.update([0]);
+        }
+        hasher.update(mask_timings(self.test_output).as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Test output with run times (`0.53 s`, `12ms`, `(0.00s)`) replaced by a
+/// placeholder and Vitest's wall-clock `Start at` line dropped, so two runs of
+/// the same code produce the same text
+fn mask_timings(output: &str) -> String {
+    output
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("Start at"))
+        .map(mask_durations)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn mask_durations(line: &str) -> String {
+    const UNITS: [&str; 4] = ["ms", "µs", "ns", "s"];
+    let mut masked = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+        let (before, from) = rest.split_at(start);
+        masked.push_str(before);
+        let number_len = from
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(from.len());
+        let (number, after) = from.split_at(number_len);
+
+        // A number glued to a word ("v8s", "test2s") isn't a duration
+        let in_word = before
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let unit_text = after.strip_prefix(' ').unwrap_or(after);
+        let unit = UNITS.iter().find(|unit| {
+            unit_text.starts_with(**unit)
+                && !unit_text[unit.len()..].starts_with(|c: char| c.is_alphanumeric())
+        });
+        match unit {
+            Some(unit) if !in_word => {
+                masked.push_str("<time>");
+                rest = &unit_text[unit.len()..];
+            }
+            _ => {
+                masked.push_str(number);
+                rest = after;
+            }
+        }
+    }
+    masked.push_str(rest);
+    masked
+}
+
+/// Fill the course's template (or the default one) with the exercise context
+pub fn build_hint_prompt(template: Option<&str>, context: &HintContext) -> String {
+    let exercise = context.exercise;
+    let files = format_files(&exercise.path, &exercise.language, context.files);
+    render(
+        template.unwrap_or(DEFAULT_HINT_PROMPT),
+        &[
+            ("title", &exercise.title),
+            ("description", &exercise.description),
+            ("language", language_name(&exercise.language)),
+            ("files", &files),
+            ("test_output", context.test_output),
+        ],
+    )
+}
+
+/// Substitute `{{name}}` placeholders in one pass, so placeholder-like text in
+/// student code or test output is left alone. Unknown placeholders are kept as-is.
+fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let name = after[..end].trim();
+            values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| (*value, end))
+        });
+
+        match value {
+            Some((value, end)) => {
+                output.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                output.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Human-readable language name, worded so "the following {{language}} exercise"
+/// still reads naturally when the language is unknown
+pub fn language_name(language: &Language) -> &'static str {
+    match language {
+        Language::JavaScript => "JavaScript",
+        Language::Python => "Python",
+        Language::Rust => "Rust",
+        Language::Go => "Go",
+        Language::Unknown => "programming",
+    }
+}
+
+/// Render source files as fenced code blocks labelled with their path
+fn format_files(exercise_dir: &Path, language: &Language, files: &[(PathBuf, String)]) -> String {
+    match files {
+        [] => String::from("(No source files could be read for this exercise.)"),
+        [(path, content)] => format!(
+            "Current code:\n```{}\n{}\n```",
+            fence_language(path, language),
+            content
+        ),
+        _ => {
+            let mut section = String::from("Current code files:\n");
+            for (path, content) in files {
+                let name = path.strip_prefix(exercise_dir).unwrap_or(path);
+                section.push_str(&format!(
+                    "\nFile: {}\n```{}\n{}\n```\n",
+                    name.display(),
+                    fence_language(path, language),
+                    content
+                ));
+            }
+            section
+        }
+    }
+}
+
+/// Code fence tag for a file, from its extension or else the exercise language
+fn fence_language(path: &Path, language: &Language) -> &'static str {
+    let by_extension = match path.extension().and_then(|e| e.to_str()) {
+        Some("js" | "mjs" | "cjs") => Some("javascript"),
+        Some("jsx") => Some("jsx"),
+        Some("ts") => Some("typescript"),
+        Some("tsx") => Some("tsx"),
+        Some("py") => Some("python"),
+        Some("rs") => Some("rust"),
+        Some("go") => Some("go"),
+        Some("html") => Some("html"),
+        Some("css") => Some("css"),
+        Some("scss") => Some("scss"),
+        Some("vue") => Some("vue"),
+        Some("json") => Some("json"),
+        Some("toml") => Some("toml"),
+        Some("md") => Some("markdown"),
+        _ => None,
+    };
+
+    by_extension.unwrap_or(match language {
+        Language::JavaScript => "javascript",
+        Language::Python => "python",
+        Language::Rust => "rust",
+        Language::Go => "go",
+        Language::Unknown => "",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exercise(language: Language) -> Exercise {
+        Exercise {
+            title: "Sum".to_string(),
+            description: "Add two numbers".to_string(),
+            language,
+            ..Exercise::for_test("basics/sum", Path::new("/course/exercises/001-sum"))
+        }
+    }
+
+    #[test]
+    fn test_default_prompt_uses_exercise_language() {
+        let exercise = exercise(Language::Python);
+        let files = vec![
+            (
+                exercise.path.join("exercise.py"),
+                "def add(a, b): pass".to_string(),
+            ),
+            (exercise.path.join("helpers/util.py"), "X = 1".to_string()),
+        ];
+        let prompt = build_hint_prompt(
+            None,
+            &HintContext {
+                exercise: &exercise,
+                files: &files,
+                test_output: "assert 3 == None",
+            },
+        );
+
+        assert!(prompt.contains("following Python exercise"));
+        assert!(prompt.contains("File: exercise.py\n```python\ndef add(a, b): pass\n```"));
+        assert!(prompt.contains("File: helpers/util.py\n```python"));
+        assert!(prompt.contains("assert 3 == None"));
+        assert!(!prompt.contains("javascript"));
+    }
+
+    #[test]
+    fn test_course_template_placeholders() {
+        let exercise = exercise(Language::Go);
+        let files = vec![(exercise.path.join("main.go"), "package main".to_string())];
+        let prompt = build_hint_prompt(
+            Some("{{language}}: {{title}} - {{description}}\n{{files}}\n{{test_output}}"),
+            &HintContext {
+                exercise: &exercise,
+                files: &files,
+                test_output: "{{title}} FAIL",
+            },
+        );
+
+        assert_eq!(
+            prompt,
+            "Go: Sum - Add two numbers\nCurrent code:\n```go\npackage main\n```\n{{title}} FAIL"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_changes_with_code_and_output() {
+        let exercise = exercise(Language::Rust);
+        let files = vec![(exercise.path.join("src/lib.rs"), "fn a() {}".to_string())];
+        let edited = vec![(exercise.path.join("src/lib.rs"), "fn b() {}".to_string())];
+        let context = |files, test_output| HintContext {
+            exercise: &exercise,
+            files,
+            test_output,
+        };
+
+        let original = context(&files, "FAIL").fingerprint();
+        assert_eq!(original, context(&files, "FAIL").fingerprint());
+        assert_ne!(original, context(&edited, "FAIL").fingerprint());
+        assert_ne!(original, context(&files, "FAIL again").fingerprint());
+        assert_eq!(
+            context(&files, "1 failed in 0.12s").fingerprint(),
+            context(&files, "1 failed in 0.53s").fingerprint()
+        );
+
+        // Only the fingerprint ignores timings; the tutor sees the real output
+        let output = "expected \"10s\" to equal \"15s\"\nStart at  12:00:01";
+        assert!(build_hint_prompt(None, &context(&files, output)).contains(output));
+    }
+
+    #[test]
+    fn test_mask_timings() {
+        let output = "Tests:       1 failed, 2 skipped\n\
+                      Time:        0.53 s\n\
+                      Start at  12:00:01\n\
+                      --- FAIL: TestSum (0.00s)\n\
+                      FAIL\texample.com/sum\t0.005s\n\
+                      Duration  1.20s (transform 20ms)\n\
+                      expected 12 to be 3 at sum.test.js:4:5";
+        assert_eq!(
+            mask_timings(output),
+            "Tests:       1 failed, 2 skipped\n\
+             Time:        <time>\n\
+             --- FAIL: TestSum (<time>)\n\
+             FAIL\texample.com/sum\t<time>\n\
+             Duration  <time> (transform <time>)\n\
+             expected 12 to be 3 at sum.test.js:4:5"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use crate::course::Exercise;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// Indices into `exercises` matching `query`, best match first. Titles count
+/// double so a title hit beats the same letters scattered through a description.
+/// An empty query lists everything in course order.
+pub fn search(exercises: &[Exercise], query: &str) -> Vec<usize> {
+    let query = query.trim();
+    if query.is_empty() {
+        return (0..exercises.len()).collect();
+    }
+
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut matches: Vec<(i64, usize)> = exercises
+        .iter()
+        .enumerate()
+        .filter_map(|(index, exercise)| {
+            let title = matcher
+                .fuzzy_match(&exercise.title, query)
+                .map(|score| score * 2);
+            let tags = exercise.metadata.tags.iter().flatten();
+            let others = [&exercise.id, &exercise.description]
+                .into_iter()
+                .chain(tags)
+                .filter_map(|field| matcher.fuzzy_match(field, query));
+            let score = title.into_iter().chain(others).max()?;
+            Some((score, index))
+        })
+        .collect();
+
+    // Stable sort keeps course order among equal scores
+    matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    matches.into_iter().map(|(_, index)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::course::ExerciseMetadata;
+    use std::path::Path;
+
+    fn exercise(id: &str, title: &str, description: &str, tags: &[&str]) -> Exercise {
+        Exercise {
+            title: title.to_string(),
+            description: description.to_string(),
+            metadata: ExerciseMetadata {
+                tags: Some(tags.iter().map(|t| t.to_string()).collect()),
+                ..Default::default()
+            },
+            ..Exercise::for_test(id, &Path::new("/course/exercises").join(id))
+        }
+    }
+
+    #[test]
+    fn test_matches_every_field_with_titles_first() {
+        let exercises = vec![
+            exercise("basics/hello-world", "Hello World", "Print a greeting", &[]),
+            exercise(
+                "basics/loops",
+                "Loops",
+                "Repeat with for and while",
+                &["control-flow"],
+            ),
+            exercise("arrays/for-each", "Iterating", "Use forEach on arrays", &[]),
+            exercise("objects/intro", "Objects", "Key and value pairs", &[]),
+        ];
+
+        assert_eq!(search(&exercises, "hlowrld"), [0]);
+        assert_eq!(search(&exercises, "control"), [1]);
+        assert_eq!(search(&exercises, "arrays/"), [2]);
+        assert_eq!(search(&exercises, "LOOPS")[0], 1);
+        assert_eq!(search(&exercises, "  "), [0, 1, 2, 3]);
+        assert!(search(&exercises, "zzz").is_empty());
+    }
+}
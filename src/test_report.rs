@@ -0,0 +1,564 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Machine-readable report formats produced by common test runners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportFormat {
+    /// `jest --json`
+    Jest,
+    /// `vitest --reporter=json` (same shape as Jest)
+    Vitest,
+    /// JUnit XML, e.g. `pytest --junitxml=report.xml`
+    Junit,
+    /// `cargo test -- -Z unstable-options --format json`
+    Libtest,
+    /// `go test -json`
+    GoTest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestCaseStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    pub name: String,
+    pub status: TestCaseStatus,
+    pub duration: Option<Duration>,
+    pub failure_message: Option<String>,
+}
+
+/// Per-test-case results parsed from a test runner report
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TestSummary {
+    pub cases: Vec<TestCase>,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub duration: Option<Duration>,
+}
+
+impl TestSummary {
+    fn from_cases(cases: Vec<TestCase>, duration: Option<Duration>) -> Self {
+        let count = |status| cases.iter().filter(|c| c.status == status).count();
+        let passed = count(TestCaseStatus::Passed);
+        let failed = count(TestCaseStatus::Failed);
+        let skipped = count(TestCaseStatus::Skipped);
+
+        // Fall back to the sum of case durations when the report has no total
+        let duration = duration.or_else(|| {
+            let durations: Vec<Duration> = cases.iter().filter_map(|c| c.duration).collect();
+            if durations.is_empty() {
+                None
+            } else {
+                Some(durations.iter().sum())
+            }
+        });
+
+        Self {
+            cases,
+            passed,
+            failed,
+            skipped,
+            duration,
+        }
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &TestCase> {
+        self.cases
+            .iter()
+            .filter(|c| c.status == TestCaseStatus::Failed)
+    }
+
+    /// Short human-readable counts, e.g. "3 passed, 1 failed, 2 skipped (0.42s)"
+    pub fn counts_text(&self) -> String {
+        let mut parts = vec![
+            format!("{} passed", self.passed),
+            format!("{} failed", self.failed),
+        ];
+        if self.skipped > 0 {
+            parts.push(format!("{} skipped", self.skipped));
+        }
+        let mut text = parts.join(", ");
+        if let Some(duration) = self.duration {
+            text.push_str(&format!(" ({:.2}s)", duration.as_secs_f64()));
+        }
+        text
+    }
+}
+
+/// Parse a report in a known format
+pub fn parse_report(format: ReportFormat, content: &str) -> Option<TestSummary> {
+    match format {
+        ReportFormat::Jest | ReportFormat::Vitest => parse_jest_json(content),
+        ReportFormat::Junit => parse_junit_xml(content),
+        ReportFormat::Libtest => parse_libtest_json(content),
+        ReportFormat::GoTest => parse_go_test_json(content),
+    }
+}
+
+/// Try every known format against captured output and return the first match
+pub fn detect_and_parse(content: &str) -> Option<TestSummary> {
+    if content.contains("<testsuite") {
+        if let Some(summary) = parse_junit_xml(content) {
+            return Some(summary);
+        }
+    }
+
+    parse_jest_json(content)
+        .or_else(|| parse_libtest_json(content))
+        .or_else(|| parse_go_test_json(content))
+}
+
+fn seconds(value: Option<f64>) -> Option<Duration> {
+    value
+        .filter(|s| s.is_finite() && *s >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+fn parse_jest_json(content: &str) -> Option<TestSummary> {
+    // The report is usually the whole output, but package managers may print
+    // a banner around it, so also look for a line holding the JSON object
+    let report: Value = serde_json::from_str(content.trim())
+        .ok()
+        .filter(|v: &Value| v.get("testResults").is_some())
+        .or_else(|| {
+            content
+                .lines()
+                .filter(|line| line.trim_start().starts_with('{'))
+                .filter_map(|line| serde_json::from_str::<Value>(line.trim()).ok())
+                .find(|v| v.get("testResults").is_some())
+        })?;
+
+    let mut cases = Vec::new();
+    let mut total_ms = 0.0;
+    let mut has_timing = false;
+
+    for file in report.get("testResults")?.as_array()? {
+        if let (Some(start), Some(end)) = (
+            file.get("startTime").and_then(Value::as_f64),
+            file.get("endTime").and_then(Value::as_f64),
+        ) {
+            total_ms += (end - start).max(0.0);
+            has_timing = true;
+        }
+
+        let assertions = file
+            .get("assertionResults")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        // A suite that failed to run (syntax error, missing import) has no assertions
+        if assertions.is_empty() {
+            if file.get("status").and_then(Value::as_str) == Some("failed") {
+                let name = file
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("test suite")
+                    .to_string();
+                let message = file
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .filter(|m| !m.is_empty())
+                    .map(str::to_string);
+                cases.push(TestCase {
+                    name,
+                    status: TestCaseStatus::Failed,
+                    duration: None,
+                    failure_message: message,
+                });
+            }
+            continue;
+        }
+
+        for assertion in assertions {
+            let name = assertion
+                .get("fullName")
+                .or_else(|| assertion.get("title"))
+                .and_then(Value::as_str)
+                .unwrap_or("unnamed test")
+                .to_string();
+            let status = match assertion.get("status").and_then(Value::as_str) {
+                Some("passed") => TestCaseStatus::Passed,
+                Some("failed") => TestCaseStatus::Failed,
+                _ => TestCaseStatus::Skipped,
+            };
+            let duration = assertion
+                .get("duration")
+                .and_then(Value::as_f64)
+                .and_then(|ms| seconds(Some(ms / 1000.0)));
+            let failure_message = assertion
+                .get("failureMessages")
+                .and_then(Value::as_array)
+                .map(|messages| {
+                    messages
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .filter(|m| !m.is_empty());
+
+            cases.push(TestCase {
+                name,
+                status,
+                duration,
+                failure_message,
+            });
+        }
+    }
+
+    let duration = if has_timing {
+        seconds(Some(total_ms / 1000.0))
+    } else {
+        None
+    };
+    Some(TestSummary::from_cases(cases, duration))
+}
+
+fn parse_junit_xml(content: &str) -> Option<TestSummary> {
+    // Skip anything printed before the XML declaration or root element
+    let start = content
+        .find("<?xml")
+        .or_else(|| content.find("<testsuites"))
+        .or_else(|| content.find("<testsuite"))?;
+    let doc = roxmltree::Document::parse(&content[start..]).ok()?;
+
+    let mut cases = Vec::new();
+    for node in doc.descendants().filter(|n| n.has_tag_name("testcase")) {
+        let name = node.attribute("name").unwrap_or("unnamed test");
+        let name = match node.attribute("classname").filter(|c| !c.is_empty()) {
+            Some(classname) => format!("{}::{}", classname, name),
+            None => name.to_string(),
+        };
+        let duration = seconds(node.attribute("time").and_then(|t| t.parse().ok()));
+
+        let failure = node
+            .children()
+            .find(|c| c.has_tag_name("failure") || c.has_tag_name("error"));
+        let skipped = node.children().any(|c| c.has_tag_name("skipped"));
+
+        let (status, failure_message) = if let Some(failure) = failure {
+            let message = failure
+                .attribute("message")
+                .map(str::to_string)
+                .or_else(|| failure.text().map(|t| t.trim().to_string()))
+                .filter(|m| !m.is_empty());
+            (TestCaseStatus::Failed, message)
+        } else if skipped {
+            (TestCaseStatus::Skipped, None)
+        } else {
+            (TestCaseStatus::Passed, None)
+        };
+
+        cases.push(TestCase {
+            name,
+            status,
+            duration,
+            failure_message,
+        });
+    }
+
+    // Only top-level suites carry the total time; nested suites would double count
+    let suite_times: Vec<f64> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("testsuite"))
+        .filter(|n| !n.ancestors().skip(1).any(|a| a.has_tag_name("testsuite")))
+        .filter_map(|n| n.attribute("time").and_then(|t| t.parse().ok()))
+        .collect();
+    let duration = if suite_times.is_empty() {
+        None
+    } else {
+        seconds(Some(suite_times.iter().sum()))
+    };
+
+    Some(TestSummary::from_cases(cases, duration))
+}
+
+fn json_lines(content: &str) -> impl Iterator<Item = Value> + '_ {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+}
+
+fn parse_libtest_json(content: &str) -> Option<TestSummary> {
+    let mut cases = Vec::new();
+    let mut suite_time = 0.0;
+    let mut has_suite_time = false;
+    let mut found = false;
+
+    for event in json_lines(content) {
+        let kind = event.get("type").and_then(Value::as_str);
+        let action = event.get("event").and_then(Value::as_str);
+
+        match kind {
+            Some("suite") => {
+                found = true;
+                if let Some(time) = event.get("exec_time").and_then(Value::as_f64) {
+                    suite_time += time;
+                    has_suite_time = true;
+                }
+            }
+            Some("test") => {
+                found = true;
+                let status = match action {
+                    Some("ok") => TestCaseStatus::Passed,
+                    Some("failed") => TestCaseStatus::Failed,
+                    Some("ignored") => TestCaseStatus::Skipped,
+                    _ => continue,
+                };
+                let name = event
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unnamed test")
+                    .to_string();
+                let failure_message = if status == TestCaseStatus::Failed {
+                    event
+                        .get("stdout")
+                        .or_else(|| event.get("message"))
+                        .and_then(Value::as_str)
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                } else {
+                    None
+                };
+
+                cases.push(TestCase {
+                    name,
+                    status,
+                    duration: seconds(event.get("exec_time").and_then(Value::as_f64)),
+                    failure_message,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if !found {
+        return None;
+    }
+    let duration = if has_suite_time {
+        seconds(Some(suite_time))
+    } else {
+        None
+    };
+    Some(TestSummary::from_cases(cases, duration))
+}
+
+fn parse_go_test_json(content: &str) -> Option<TestSummary> {
+    let mut cases = Vec::new();
+    let mut outputs: HashMap<String, Vec<String>> = HashMap::new();
+    let mut package_outputs: HashMap<String, Vec<String>> = HashMap::new();
+    let mut packages_with_tests: Vec<String> = Vec::new();
+    let mut package_time = 0.0;
+    let mut has_package_time = false;
+    let mut found = false;
+
+    for event in json_lines(content) {
+        let Some(action) = event.get("Action").and_then(Value::as_str) else {
+            continue;
+        };
+        found = true;
+
+        let package = event
+            .get("Package")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let test = event.get("Test").and_then(Value::as_str);
+        let elapsed = event.get("Elapsed").and_then(Value::as_f64);
+
+        match (action, test) {
+            ("output", Some(test)) => {
+                let line = event.get("Output").and_then(Value::as_str).unwrap_or("");
+                outputs
+                    .entry(test.to_string())
+                    .or_default()
+                    .push(line.trim_end().to_string());
+            }
+            ("output", None) => {
+                let line = event.get("Output").and_then(Value::as_str).unwrap_or("");
+                package_outputs
+                    .entry(package)
+                    .or_default()
+                    .push(line.trim_end().to_string());
+            }
+            ("pass" | "fail" | "skip", Some(test)) => {
+                let status = match action {
+                    "pass" => TestCaseStatus::Passed,
+                    "fail" => TestCaseStatus::Failed,
+                    _ => TestCaseStatus::Skipped,
+                };
+                let failure_message = if status == TestCaseStatus::Failed {
+                    outputs
+                        .get(test)
+                        .map(|lines| {
+                            lines
+                                .iter()
+                                .filter(|l| !l.starts_with("=== ") && !l.is_empty())
+                                .cloned()
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        })
+                        .filter(|m| !m.is_empty())
+                } else {
+                    None
+                };
+                if !packages_with_tests.contains(&package) {
+                    packages_with_tests.push(package);
+                }
+                cases.push(TestCase {
+                    name: test.to_string(),
+                    status,
+                    duration: seconds(elapsed),
+                    failure_message,
+                });
+            }
+            ("pass" | "fail", None) => {
+                if let Some(time) = elapsed {
+                    package_time += time;
+                    has_package_time = true;
+                }
+                // A package that fails without running tests did not build
+                if action == "fail" && !packages_with_tests.contains(&package) {
+                    let message = package_outputs
+                        .get(&package)
+                        .map(|lines| lines.join("\n").trim().to_string())
+                        .filter(|m| !m.is_empty());
+                    cases.push(TestCase {
+                        name: package,
+                        status: TestCaseStatus::Failed,
+                        duration: None,
+                        failure_message: message,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !found {
+        return None;
+    }
+    let duration = if has_package_time {
+        seconds(Some(package_time))
+    } else {
+        None
+    };
+    Some(TestSummary::from_cases(cases, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_jest_json() {
+        let report = r#"{
+            "numTotalTests": 3,
+            "testResults": [{
+                "name": "/ex/sum.test.js",
+                "status": "failed",
+                "startTime": 1000,
+                "endTime": 1500,
+                "assertionResults": [
+                    {"fullName": "sum adds", "status": "passed", "duration": 5, "failureMessages": []},
+                    {"fullName": "sum negatives", "status": "failed", "duration": 7, "failureMessages": ["expected 2, got 3"]},
+                    {"fullName": "sum later", "status": "pending", "duration": null, "failureMessages": []}
+                ]
+            }]
+        }"#;
+
+        let summary = parse_report(ReportFormat::Jest, report).unwrap();
+        assert_eq!(summary.passed, 1);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.duration, Some(Duration::from_millis(500)));
+
+        let failure = summary.failures().next().unwrap();
+        assert_eq!(failure.name, "sum negatives");
+        assert_eq!(
+            failure.failure_message.as_deref(),
+            Some("expected 2, got 3")
+        );
+    }
+
+    #[test]
+    fn test_parse_junit_xml() {
+        let report = r#"<?xml version="1.0" encoding="utf-8"?>
+<testsuites><testsuite name="pytest" tests="3" time="0.250">
+  <testcase classname="test_math" name="test_add" time="0.010"/>
+  <testcase classname="test_math" name="test_div" time="0.020">
+    <failure message="ZeroDivisionError">traceback</failure>
+  </testcase>
+  <testcase classname="test_math" name="test_todo" time="0.000"><skipped/></testcase>
+</testsuite></testsuites>"#;
+
+        let summary = parse_report(ReportFormat::Junit, report).unwrap();
+        assert_eq!((summary.passed, summary.failed, summary.skipped), (1, 1, 1));
+        assert_eq!(summary.duration, Some(Duration::from_millis(250)));
+        let failure = summary.failures().next().unwrap();
+        assert_eq!(failure.name, "test_math::test_div");
+        assert_eq!(
+            failure.failure_message.as_deref(),
+            Some("ZeroDivisionError")
+        );
+    }
+
+    #[test]
+    fn test_parse_libtest_json() {
+        let output = r#"{ "type": "suite", "event": "started", "test_count": 2 }
+{ "type": "test", "event": "started", "name": "tests::adds" }
+{ "type": "test", "name": "tests::adds", "event": "ok" }
+{ "type": "test", "name": "tests::subtracts", "event": "failed", "stdout": "assertion failed: left == right\n" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "exec_time": 0.5 }"#;
+
+        let summary = detect_and_parse(output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (1, 1));
+        assert_eq!(summary.duration, Some(Duration::from_millis(500)));
+        assert_eq!(
+            summary
+                .failures()
+                .next()
+                .unwrap()
+                .failure_message
+                .as_deref(),
+            Some("assertion failed: left == right")
+        );
+    }
+
+    #[test]
+    fn test_parse_go_test_json() {
+        let output = r#"{"Action":"run","Package":"ex","Test":"TestAdd"}
+{"Action":"output","Package":"ex","Test":"TestAdd","Output":"=== RUN   TestAdd\n"}
+{"Action":"pass","Package":"ex","Test":"TestAdd","Elapsed":0.01}
+{"Action":"run","Package":"ex","Test":"TestSub"}
+{"Action":"output","Package":"ex","Test":"TestSub","Output":"    sub_test.go:9: got 3, want 1\n"}
+{"Action":"fail","Package":"ex","Test":"TestSub","Elapsed":0.02}
+{"Action":"fail","Package":"ex","Elapsed":0.2}"#;
+
+        let summary = detect_and_parse(output).unwrap();
+        assert_eq!((summary.passed, summary.failed), (1, 1));
+        assert_eq!(summary.duration, Some(Duration::from_millis(200)));
+        let failure = summary.failures().next().unwrap();
+        assert_eq!(failure.name, "TestSub");
+        assert_eq!(
+            failure.failure_message.as_deref(),
+            Some("    sub_test.go:9: got 3, want 1")
+        );
+    }
+
+    #[test]
+    fn test_plain_output_is_not_parsed() {
+        assert!(detect_and_parse("PASS  all good\n2 tests passed").is_none());
+    }
+}
@@ -1,9 +1,12 @@
 use crate::course::Exercise;
+use crate::test_report::{self, TestSummary};
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command as TokioCommand;
+use tokio::process::{Child, Command as TokioCommand};
 use tokio::sync::mpsc;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -11,6 +14,55 @@ pub enum TestResult {
     Passed,
     Failed,
     Error(String),
+    /// The test command exceeded the exercise's time limit and was killed
+    Timeout(Duration),
+    /// The run was cancelled before it finished; nothing should be recorded
+    Cancelled,
+}
+
+impl TestResult {
+    /// Stable lowercase name used when storing results
+    pub fn label(&self) -> &'static str {
+        match self {
+            TestResult::Passed => "passed",
+            TestResult::Failed => "failed",
+            TestResult::Error(_) => "error",
+            TestResult::Timeout(_) => "timeout",
+            TestResult::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Result of a single test run, with per-test-case details when the runner's
+/// report could be parsed
+#[derive(Debug, Clone)]
+pub struct TestOutcome {
+    pub result: TestResult,
+    pub summary: Option<TestSummary>,
+    /// Wall-clock time of the test command (setup excluded)
+    pub duration: Option<Duration>,
+    pub exit_code: Option<i32>,
+    /// Combined stdout and stderr of the test command
+    pub output: String,
+}
+
+impl TestOutcome {
+    pub fn new(result: TestResult) -> Self {
+        Self {
+            result,
+            summary: None,
+            duration: None,
+            exit_code: None,
+            output: String::new(),
+        }
+    }
+}
+
+/// How a spawned command finished
+enum WaitOutcome {
+    Exited(ExitStatus),
+    TimedOut,
+    Cancelled,
 }
 
 #[derive(Clone)]
@@ -26,9 +78,14 @@ impl TestRunner {
         }
     }
 
-    /// Run setup command for an exercise if one exists
-    /// Returns true if setup was run, false if skipped
-    async fn run_setup(&self, exercise: &Exercise, tx: &mpsc::Sender<String>) -> Result<bool> {
+    /// Run setup command for an exercise if one exists, under the exercise's
+    /// time limit. Returns false if the run was cancelled during setup
+    async fn run_setup(
+        &self,
+        exercise: &Exercise,
+        tx: &mpsc::Sender<String>,
+        cancel_rx: &mut mpsc::Receiver<()>,
+    ) -> Result<bool> {
         if let Some(setup_cmd) = exercise.get_setup_command() {
             // Mark the start of setup output
             let _ = tx.send("__SETUP_START__\n".to_string()).await;
@@ -64,10 +121,21 @@ impl TestRunner {
                 });
             }
 
-            let status = child
-                .wait()
+            let timeout = exercise.get_timeout();
+            let status = match wait_for_child(&mut child, Some(timeout), cancel_rx)
                 .await
-                .context("Failed to wait for setup command")?;
+                .context("Failed to wait for setup command")?
+            {
+                WaitOutcome::Exited(status) => status,
+                WaitOutcome::TimedOut => {
+                    let _ = tx.send("__SETUP_FAILED__\n".to_string()).await;
+                    anyhow::bail!(
+                        "Setup timed out after {}s and was stopped",
+                        timeout.as_secs()
+                    );
+                }
+                WaitOutcome::Cancelled => return Ok(false),
+            };
 
             if !status.success() {
                 // Mark setup as failed - keep the output visible
@@ -77,13 +145,14 @@ impl TestRunner {
 
             // Mark setup as successful - this signals UI to hide setup output
             let _ = tx.send("__SETUP_SUCCESS__\n".to_string()).await;
-            Ok(true)
-        } else {
-            Ok(false)
         }
+        Ok(true)
     }
 
     /// Create a command from a command string
+    ///
+    /// On Unix the command gets its own process group so that everything it
+    /// spawns (e.g. `pnpm` -> `node`) can be killed together.
     fn create_command(&self, command_str: &str, working_dir: &Path) -> TokioCommand {
         let mut cmd = if cfg!(target_os = "windows") {
             let mut c = TokioCommand::new("cmd");
@@ -97,21 +166,44 @@ impl TestRunner {
             c
         };
 
+        #[cfg(unix)]
+        cmd.process_group(0);
+
         cmd.current_dir(working_dir);
+        cmd.kill_on_drop(true);
         cmd
     }
 
     /// Run tests with streaming output
+    ///
+    /// Sending on (or dropping the sender of) `cancel_rx` kills the running
+    /// command's whole process tree and returns `TestResult::Cancelled`.
     pub async fn run_test_streaming(
         &self,
         exercise: &Exercise,
         tx: mpsc::Sender<String>,
-    ) -> Result<TestResult> {
+        cancel_rx: &mut mpsc::Receiver<()>,
+    ) -> Result<TestOutcome> {
         // Run setup first if needed
-        if let Err(e) = self.run_setup(exercise, &tx).await {
-            let error_msg = format!("Setup failed: {}", e);
-            let _ = tx.send(error_msg.clone()).await;
-            return Ok(TestResult::Error(error_msg));
+        match self.run_setup(exercise, &tx, cancel_rx).await {
+            Ok(true) => {}
+            Ok(false) => return Ok(TestOutcome::new(TestResult::Cancelled)),
+            Err(e) => {
+                let error_msg = format!("Setup failed: {}", e);
+                let _ = tx.send(error_msg.clone()).await;
+                return Ok(TestOutcome::new(TestResult::Error(error_msg)));
+            }
+        }
+
+        // Remove a stale report so a crashed run can't be read as a fresh one
+        let report_path = exercise
+            .metadata
+            .test_report
+            .as_ref()
+            .and_then(|report| report.path.as_ref())
+            .map(|path| exercise.path.join(path));
+        if let Some(ref path) = report_path {
+            let _ = std::fs::remove_file(path);
         }
 
         let _ = tx
@@ -123,62 +215,291 @@ impl TestRunner {
 
         let test_cmd = exercise.get_test_command();
 
-        let mut child = if cfg!(target_os = "windows") {
-            TokioCommand::new("cmd")
-                .arg("/C")
-                .arg(&test_cmd)
-                .current_dir(&exercise.path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .context(format!("Failed to spawn test command: {}", test_cmd))?
-        } else {
-            TokioCommand::new("sh")
-                .arg("-c")
-                .arg(&test_cmd)
-                .current_dir(&exercise.path)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .context(format!("Failed to spawn test command: {}", test_cmd))?
-        };
+        let mut cmd = self.create_command(&test_cmd, &exercise.path);
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let started = Instant::now();
+        let mut child = cmd
+            .spawn()
+            .context(format!("Failed to spawn test command: {}", test_cmd))?;
 
         let stdout = child.stdout.take().context("Failed to capture stdout")?;
         let stderr = child.stderr.take().context("Failed to capture stderr")?;
 
+        // Keep stdout around so a structured report printed there can be parsed,
+        // and both streams interleaved for the attempt history.
+        // Both streams are drained even if nobody is listening any more, so the
+        // child never blocks on a full pipe.
+        let output = Arc::new(Mutex::new(String::new()));
+
         let tx_clone = tx.clone();
+        let output_clone = output.clone();
         let stdout_handle = tokio::spawn(async move {
             let reader = BufReader::new(stdout);
             let mut lines = reader.lines();
+            let mut captured = Vec::new();
             while let Ok(Some(line)) = lines.next_line().await {
-                if tx_clone.send(line + "\n").await.is_err() {
-                    break;
-                }
+                captured.push(line.clone());
+                push_line(&output_clone, &line);
+                let _ = tx_clone.send(line + "\n").await;
             }
+            captured
         });
 
         let tx_clone = tx.clone();
+        let output_clone = output.clone();
         let stderr_handle = tokio::spawn(async move {
             let reader = BufReader::new(stderr);
             let mut lines = reader.lines();
             while let Ok(Some(line)) = lines.next_line().await {
-                if tx_clone.send(line + "\n").await.is_err() {
-                    break;
-                }
+                push_line(&output_clone, &line);
+                let _ = tx_clone.send(line + "\n").await;
             }
         });
 
-        // Wait for both streams to finish
-        let _ = tokio::join!(stdout_handle, stderr_handle);
+        // Wait for the process to complete, killing the whole process tree
+        // if it runs past the time limit or the run is cancelled
+        let timeout = exercise.get_timeout();
+        let waited = wait_for_child(&mut child, Some(timeout), cancel_rx)
+            .await
+            .context("Failed to wait for npm test")?;
+        let duration = started.elapsed();
 
-        // Wait for the process to complete
-        let status = child.wait().await.context("Failed to wait for npm test")?;
+        // Wait for both streams to finish; they close once the process tree is gone
+        let (stdout_lines, _) = tokio::join!(stdout_handle, stderr_handle);
+        let output = std::mem::take(&mut *output.lock().unwrap());
 
-        if status.success() {
-            Ok(TestResult::Passed)
-        } else {
-            // Check if tests failed vs error - we'll determine this from output
-            Ok(TestResult::Failed)
+        let status = match waited {
+            WaitOutcome::Exited(status) => status,
+            WaitOutcome::TimedOut => {
+                let _ = tx
+                    .send(format!(
+                        "\nTests timed out after {}s and were stopped.\n",
+                        timeout.as_secs()
+                    ))
+                    .await;
+                return Ok(TestOutcome {
+                    duration: Some(duration),
+                    output,
+                    ..TestOutcome::new(TestResult::Timeout(timeout))
+                });
+            }
+            WaitOutcome::Cancelled => {
+                return Ok(TestOutcome::new(TestResult::Cancelled));
+            }
+        };
+
+        let stdout_text = stdout_lines.unwrap_or_default().join("\n");
+        let summary = Self::parse_summary(exercise, report_path.as_deref(), &stdout_text);
+        let result = Self::determine_result(status.success(), status.code(), summary.as_ref());
+
+        Ok(TestOutcome {
+            result,
+            summary,
+            duration: Some(duration),
+            exit_code: status.code(),
+            output,
+        })
+    }
+
+    /// Parse the configured report (file or stdout), or auto-detect one in stdout
+    fn parse_summary(
+        exercise: &Exercise,
+        report_path: Option<&Path>,
+        stdout: &str,
+    ) -> Option<TestSummary> {
+        match exercise.metadata.test_report {
+            Some(ref report) => match report_path {
+                Some(path) => std::fs::read_to_string(path)
+                    .ok()
+                    .and_then(|content| test_report::parse_report(report.format, &content)),
+                None => test_report::parse_report(report.format, stdout),
+            },
+            None => test_report::detect_and_parse(stdout),
+        }
+    }
+
+    /// Decide pass/fail/error from the exit status and the parsed report
+    fn determine_result(
+        success: bool,
+        exit_code: Option<i32>,
+        summary: Option<&TestSummary>,
+    ) -> TestResult {
+        match summary {
+            Some(summary) if summary.failed > 0 => TestResult::Failed,
+            _ if success => TestResult::Passed,
+            // The runner failed but no test case did: it never got to run the tests
+            Some(_) => TestResult::Error(match exit_code {
+                Some(code) => format!("Test command exited with code {} but no test failed", code),
+                None => String::from("Test command was terminated before reporting results"),
+            }),
+            None => TestResult::Failed,
         }
     }
 }
+
+fn push_line(output: &Mutex<String>, line: &str) {
+    let mut output = output.lock().unwrap();
+    output.push_str(line);
+    output.push('\n');
+}
+
+/// Whether an output line is one of the setup progress markers the TUI interprets
+pub fn is_setup_marker(line: &str) -> bool {
+    matches!(
+        line.trim_end(),
+        "__SETUP_START__" | "__SETUP_SUCCESS__" | "__SETUP_FAILED__"
+    )
+}
+
+/// Wait for a child to exit, up to an optional time limit or until cancelled.
+/// The child's whole process tree is killed afterwards in every case, which also
+/// stops anything it left running in the background so its output streams close.
+async fn wait_for_child(
+    child: &mut Child,
+    timeout: Option<Duration>,
+    cancel_rx: &mut mpsc::Receiver<()>,
+) -> Result<WaitOutcome> {
+    let pid = child.id();
+
+    let outcome = {
+        let wait = async {
+            match timeout {
+                Some(limit) => tokio::time::timeout(limit, child.wait()).await.ok(),
+                None => Some(child.wait().await),
+            }
+        };
+
+        tokio::select! {
+            waited = wait => match waited {
+                Some(status) => WaitOutcome::Exited(status?),
+                None => WaitOutcome::TimedOut,
+            },
+            // Fires on an explicit cancel and when the sender is dropped
+            _ = cancel_rx.recv() => WaitOutcome::Cancelled,
+        }
+    };
+
+    if let Some(pid) = pid {
+        kill_process_tree(pid).await;
+    }
+    if !matches!(outcome, WaitOutcome::Exited(_)) {
+        let _ = child.kill().await;
+    }
+
+    Ok(outcome)
+}
+
+/// Kill a process and every process it spawned
+#[cfg(unix)]
+pub async fn kill_process_tree(pid: u32) {
+    // Commands are spawned as process group leaders, so the group id is the pid
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+/// Kill a process and every process it spawned
+#[cfg(windows)]
+pub async fn kill_process_tree(pid: u32) {
+    let _ = TokioCommand::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::course::ExerciseMetadata;
+    use tempfile::TempDir;
+
+    fn exercise_with_command(path: &Path, test_command: &str, timeout_seconds: u64) -> Exercise {
+        Exercise {
+            metadata: ExerciseMetadata {
+                test_command: Some(test_command.to_string()),
+                timeout_seconds: Some(timeout_seconds),
+                ..Default::default()
+            },
+            ..Exercise::for_test("test", path)
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout_kills_process_group() {
+        let temp = TempDir::new().unwrap();
+        let marker = temp.path().join("marker");
+        // The grandchild would create the marker if it survived the timeout
+        let command = format!("(sleep 3 && touch {}) & sleep 30", marker.display());
+        let exercise = exercise_with_command(temp.path(), &command, 1);
+
+        let (tx, mut rx) = mpsc::channel(100);
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let (_cancel_tx, mut cancel_rx) = mpsc::channel(1);
+
+        let runner = TestRunner::new(temp.path());
+        let outcome = runner
+            .run_test_streaming(&exercise, tx, &mut cancel_rx)
+            .await
+            .unwrap();
+        assert_eq!(outcome.result, TestResult::Timeout(Duration::from_secs(1)));
+
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(!marker.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_setup_command_is_stopped_at_the_time_limit() {
+        let temp = TempDir::new().unwrap();
+        let mut exercise = exercise_with_command(temp.path(), "true", 1);
+        exercise.metadata.setup_command = Some("sleep 30".to_string());
+
+        let (tx, mut rx) = mpsc::channel(100);
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let (_cancel_tx, mut cancel_rx) = mpsc::channel(1);
+
+        let runner = TestRunner::new(temp.path());
+        let outcome = tokio::time::timeout(
+            Duration::from_secs(5),
+            runner.run_test_streaming(&exercise, tx, &mut cancel_rx),
+        )
+        .await
+        .expect("a hanging setup should be stopped")
+        .unwrap();
+        assert!(
+            matches!(&outcome.result, TestResult::Error(message) if message.contains("timed out")),
+            "{:?}",
+            outcome.result
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancel_stops_running_test() {
+        let temp = TempDir::new().unwrap();
+        let exercise = exercise_with_command(temp.path(), "sleep 30", 60);
+
+        let (tx, mut rx) = mpsc::channel(100);
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let (cancel_tx, mut cancel_rx) = mpsc::channel(1);
+
+        let runner = TestRunner::new(temp.path());
+        let run = tokio::spawn(async move {
+            runner
+                .run_test_streaming(&exercise, tx, &mut cancel_rx)
+                .await
+                .unwrap()
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        cancel_tx.send(()).await.unwrap();
+
+        let outcome = tokio::time::timeout(Duration::from_secs(5), run)
+            .await
+            .expect("cancelled run should finish promptly")
+            .unwrap();
+        assert_eq!(outcome.result, TestResult::Cancelled);
+    }
+}
@@ -2,8 +2,16 @@ use crate::config::Config;
 use crate::course::{Course, Exercise};
 use crate::database::Database;
 use crate::editor::{self, Editor};
+use crate::file_cache::FileCache;
+use crate::hint::{self, ChatMessage, HintProvider, Role};
+use crate::keymap::{Action, Keymap};
+use crate::markdown;
 use crate::playground;
-use crate::test_runner::{TestResult, TestRunner};
+use crate::progress::{ExerciseStatus, ProgressCache};
+use crate::prompt::{self, HintContext};
+use crate::search;
+use crate::test_report::TestSummary;
+use crate::test_runner::{TestOutcome, TestResult, TestRunner};
 use ansi_to_tui::IntoText;
 use anyhow::Result;
 use crossterm::{
@@ -22,11 +30,21 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
+use std::cell::{Ref, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
-#[derive(PartialEq)]
+/// Prompt for the AI tutor and the fingerprint its reply is cached under
+struct HintPrompt {
+    prompt: String,
+    fingerprint: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DisplayMode {
     Readme,
     ReadmeFocused,
@@ -36,6 +54,8 @@ pub enum DisplayMode {
     RunAllTests,
     PlaygroundConfirm,
     EditorSelection,
+    Search,
+    Help,
 }
 
 pub struct App {
@@ -45,11 +65,13 @@ pub struct App {
     current_exercises: Vec<Exercise>, // Exercises in current folder
     breadcrumb: Vec<String>, // Breadcrumb trail for navigation
     database: Database,
+    progress: ProgressCache,
     test_runner: TestRunner,
     selected_index: usize,
     list_state: ListState,
     display_mode: DisplayMode,
     last_test_result: Option<TestResult>,
+    last_test_summary: Option<TestSummary>,
     test_output_lines: Vec<String>,
     status_message: String,
     status_message_timestamp: Option<std::time::Instant>,
@@ -57,14 +79,37 @@ pub struct App {
     is_running_test: bool,
     scroll_position: usize,
     output_receiver: Option<mpsc::Receiver<String>>,
-    result_receiver: Option<mpsc::Receiver<TestResult>>,
+    result_receiver: Option<mpsc::Receiver<TestOutcome>>,
+    run_cancel_tx: Option<mpsc::Sender<()>>,
+    test_task: Option<JoinHandle<()>>,
     running_exercise_id: Option<String>,
     blink_toggle: bool,
     blink_counter: u8,
+    /// Rendered README of the selected exercise, reread only when it changes
+    readme_cache: FileCache<Vec<Line<'static>>>,
+    /// Author-written hints revealed so far for the selected exercise
+    revealed_hints: Vec<String>,
+    /// Conversation with the AI tutor for the selected exercise
+    hint_conversation: Vec<ChatMessage>,
+    /// Rendered Markdown of finished tutor replies by message index, so only the
+    /// reply still streaming in is rendered on every frame
+    rendered_replies: RefCell<HashMap<usize, (String, Vec<Line<'static>>)>>,
+    /// Whether the reply being generated starts a new conversation
+    hint_new_conversation: bool,
+    hint_exercise_id: Option<String>,
+    /// Model and context fingerprint the new hint is cached under
+    hint_cache_key: Option<(String, String)>,
+    /// Reply streamed in as it is generated, or the error that stopped it
     hint_text: Option<String>,
+    /// Follow-up question being typed in the hint view
+    chat_input: Option<String>,
     is_generating_hint: bool,
+    /// Exercises that had a hint generated since their last recorded attempt
+    hinted_exercises: HashSet<String>,
     hint_receiver: Option<mpsc::Receiver<String>>,
-    hint_complete_receiver: Option<mpsc::Receiver<()>>,
+    /// Sends whether the reply completed successfully
+    hint_complete_receiver: Option<mpsc::Receiver<bool>>,
+    hint_task: Option<JoinHandle<()>>,
     config: Config,
     available_models: Vec<String>,
     model_list_state: ListState,
@@ -77,8 +122,10 @@ pub struct App {
     run_all_progress: Vec<(String, Option<TestResult>)>, // (exercise_id, result)
     run_all_current_index: usize,
     run_all_output: Vec<String>,
-    run_all_receiver: Option<mpsc::Receiver<(usize, TestResult)>>,
+    run_all_case_totals: (usize, usize, usize), // (passed, failed, skipped) test cases
+    run_all_receiver: Option<mpsc::Receiver<(usize, TestOutcome)>>,
     run_all_cancel_tx: Option<mpsc::Sender<()>>,
+    run_all_task: Option<JoinHandle<()>>,
     // Setup tracking
     setup_start_index: Option<usize>, // Track where setup output starts
     // Unblock all flag
@@ -88,47 +135,59 @@ pub struct App {
     show_playground_success: bool,
     // List viewport height for smart scrolling
     list_viewport_height: usize,
-    // Course path for folder navigation
-    course_path: PathBuf,
+    // Course-wide search
+    search_query: String,
+    /// Indices into `all_exercises`, best match first
+    search_results: Vec<usize>,
+    search_list_state: ListState,
+    keymap: Keymap,
+    /// View and scroll position to return to when the help overlay closes
+    help_return: Option<(DisplayMode, usize)>,
 }
 
 impl App {
     pub fn new(course_path: PathBuf, unblock_all: bool) -> Result<Self> {
         let (course, all_exercises) = Course::load_from_path(&course_path)?;
-        let database = Database::new(&course_path)?;
+        let database = Database::open(&course_path, &all_exercises)?;
         let test_runner = TestRunner::new(&course_path);
 
         // Start in the root exercises folder
         let exercises_dir = course_path.join("exercises");
         let current_exercises = Self::load_current_folder(&all_exercises, &exercises_dir);
 
-        // Find first incomplete exercise to select on startup
-        let progress_map: std::collections::HashMap<String, bool> = database
-            .get_all_progress()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|p| (p.exercise_id, p.completed))
-            .collect();
-
-        let mut initial_index = 0;
-        for (index, exercise) in current_exercises.iter().enumerate() {
-            if exercise.is_folder {
-                continue; // Skip folders when looking for first incomplete
-            }
-            let is_completed = progress_map.get(&exercise.id).copied().unwrap_or(false);
+        let progress = ProgressCache::load(&database, &all_exercises)?;
 
-            if !is_completed {
-                initial_index = index;
-                break;
-            }
-        }
+        // Find first incomplete exercise to select on startup
+        let initial_index = progress
+            .first_incomplete_index(&current_exercises)
+            .unwrap_or(0);
 
         let mut list_state = ListState::default();
         if !current_exercises.is_empty() {
             list_state.select(Some(initial_index));
         }
 
-        let config = Config::load().unwrap_or_default();
+        let (config, config_error) = Config::load_or_default();
+        let (keymap, keymap_error) = match Keymap::from_config(&config.keybindings) {
+            Ok(keymap) => (keymap, None),
+            Err(e) => (Keymap::default(), Some(e)),
+        };
+        let default_status_message = format!(
+            "{} - run/open, {} - go back, {} - search, {} - run all tests, {} - open in editor, {} - readme, {} - help, {} - quit",
+            keymap.primary(Action::Confirm),
+            keymap.primary(Action::LeaveFolder),
+            keymap.primary(Action::Search),
+            keymap.primary(Action::RunAllTests),
+            keymap.primary(Action::OpenEditor),
+            keymap.primary(Action::ReadReadme),
+            keymap.primary(Action::Help),
+            keymap.primary(Action::Quit),
+        );
+        let status_message = match (config_error, keymap_error) {
+            (Some(e), _) => format!("Couldn't load config, using defaults: {:#}", e),
+            (None, Some(e)) => format!("Invalid keybindings in config, using defaults: {:#}", e),
+            (None, None) => default_status_message.clone(),
+        };
 
         Ok(Self {
             course,
@@ -137,30 +196,40 @@ impl App {
             current_exercises,
             breadcrumb: Vec::new(),
             database,
+            progress,
             test_runner,
             selected_index: initial_index,
             list_state,
             display_mode: DisplayMode::Readme,
             last_test_result: None,
+            last_test_summary: None,
             test_output_lines: Vec::new(),
-            status_message: String::from(
-                "Enter - run/open, Backspace - go back, Shift+A - run all tests, o - open in editor, r - readme, q - quit",
-            ),
+            status_message,
             status_message_timestamp: None,
-            default_status_message: String::from(
-                "Enter - run/open, Backspace - go back, Shift+A - run all tests, o - open in editor, r - readme, q - quit",
-            ),
+            default_status_message,
             is_running_test: false,
             scroll_position: 0,
             output_receiver: None,
             result_receiver: None,
+            run_cancel_tx: None,
+            test_task: None,
             running_exercise_id: None,
             blink_toggle: false,
             blink_counter: 0,
+            readme_cache: FileCache::new(),
+            revealed_hints: Vec::new(),
+            hint_conversation: Vec::new(),
+            rendered_replies: RefCell::new(HashMap::new()),
+            hint_new_conversation: false,
+            hint_exercise_id: None,
+            hint_cache_key: None,
             hint_text: None,
+            chat_input: None,
             is_generating_hint: false,
+            hinted_exercises: HashSet::new(),
             hint_receiver: None,
             hint_complete_receiver: None,
+            hint_task: None,
             config,
             available_models: Vec::new(),
             model_list_state: ListState::default(),
@@ -171,28 +240,26 @@ impl App {
             run_all_progress: Vec::new(),
             run_all_current_index: 0,
             run_all_output: Vec::new(),
+            run_all_case_totals: (0, 0, 0),
             run_all_receiver: None,
             run_all_cancel_tx: None,
+            run_all_task: None,
             setup_start_index: None,
-            unblock_all,
+            unblock_all: unblock_all
+                || std::env::var("LEARNP_UNBLOCK_ALL").unwrap_or_default() == "1",
             playground_path: None,
             show_playground_success: false,
             list_viewport_height: 20, // Default, will be updated during render
-            course_path,
+            search_query: String::new(),
+            search_results: Vec::new(),
+            search_list_state: ListState::default(),
+            keymap,
+            help_return: None,
         })
     }
 
-    fn load_current_folder(all_exercises: &[Exercise], folder_path: &PathBuf) -> Vec<Exercise> {
-        all_exercises
-            .iter()
-            .filter(|ex| {
-                // Check if the exercise's parent directory matches the current folder path
-                if let Some(parent) = ex.path.parent() {
-                    parent == folder_path.as_path()
-                } else {
-                    false
-                }
-            })
+    fn load_current_folder(all_exercises: &[Exercise], folder_path: &Path) -> Vec<Exercise> {
+        Course::children(all_exercises, folder_path)
             .cloned()
             .collect()
     }
@@ -228,60 +295,12 @@ impl App {
     }
 
     fn get_folder_progress(&self, folder: &Exercise) -> (usize, usize) {
-        if !folder.is_folder {
-            return (0, 0);
-        }
-
-        let progress_map: std::collections::HashMap<String, bool> = self
-            .database
-            .get_all_progress()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|p| (p.exercise_id, p.completed))
-            .collect();
-
-        // Find all exercises that are descendants of this folder
-        let mut total = 0;
-        let mut completed = 0;
-
-        for exercise in &self.all_exercises {
-            if exercise.is_folder {
-                continue; // Skip nested folders, only count exercises
-            }
-
-            // Check if this exercise is a descendant of the folder
-            if exercise.path.starts_with(&folder.path) {
-                total += 1;
-                if progress_map.get(&exercise.id).copied().unwrap_or(false) {
-                    completed += 1;
-                }
-            }
-        }
-
-        (completed, total)
+        self.progress.folder_progress(folder)
     }
 
     fn get_first_incomplete_index(&self) -> Option<usize> {
-        let progress_map: std::collections::HashMap<String, bool> = self
-            .database
-            .get_all_progress()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|p| (p.exercise_id, p.completed))
-            .collect();
-
-        for (index, exercise) in self.current_exercises.iter().enumerate() {
-            if exercise.is_folder {
-                continue; // Skip folders
-            }
-            let is_completed = progress_map.get(&exercise.id).copied().unwrap_or(false);
-
-            if !is_completed {
-                return Some(index);
-            }
-        }
-
-        None
+        self.progress
+            .first_incomplete_index(&self.current_exercises)
     }
 
     fn is_exercise_unlocked(&self, index: usize) -> bool {
@@ -299,6 +318,117 @@ impl App {
         }
     }
 
+    /// Whether an exercise anywhere in the course can be opened: it and each
+    /// folder above it must come no later than the first incomplete sibling
+    fn is_unlocked_in_course(&self, exercise: &Exercise) -> bool {
+        self.unblock_all || !self.progress.is_locked(exercise)
+    }
+
+    fn toggle_help(&mut self) {
+        if let Some((mode, scroll_position)) = self.help_return.take() {
+            self.display_mode = mode;
+            self.scroll_position = scroll_position;
+        } else {
+            self.help_return = Some((self.display_mode, self.scroll_position));
+            self.display_mode = DisplayMode::Help;
+            self.scroll_position = 0;
+        }
+    }
+
+    fn open_search(&mut self) {
+        self.display_mode = DisplayMode::Search;
+        self.search_query.clear();
+        self.update_search();
+        self.set_status(String::from(
+            "Search | type to filter, ↑/↓ - navigate, Enter - go to exercise, Esc - cancel",
+        ));
+    }
+
+    fn update_search(&mut self) {
+        self.search_results = search::search(&self.all_exercises, &self.search_query);
+        self.search_list_state = ListState::default();
+        if !self.search_results.is_empty() {
+            self.search_list_state.select(Some(0));
+        }
+    }
+
+    /// Handle a key press while the search overlay is open
+    fn handle_search_input(&mut self, key: event::KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.show_readme(),
+            KeyCode::Enter => {
+                if let Some(&index) = self
+                    .search_list_state
+                    .selected()
+                    .and_then(|selected| self.search_results.get(selected))
+                {
+                    self.jump_to_exercise(index);
+                }
+            }
+            KeyCode::Down if !self.search_results.is_empty() => {
+                let next = self
+                    .search_list_state
+                    .selected()
+                    .map_or(0, |i| (i + 1).min(self.search_results.len() - 1));
+                self.search_list_state.select(Some(next));
+            }
+            KeyCode::Up => {
+                if let Some(selected) = self.search_list_state.selected() {
+                    self.search_list_state
+                        .select(Some(selected.saturating_sub(1)));
+                }
+            }
+            KeyCode::Backspace => {
+                self.search_query.pop();
+                self.update_search();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search_query.push(c);
+                self.update_search();
+            }
+            _ => {}
+        }
+    }
+
+    /// Open the folder containing `all_exercises[index]` and select it
+    fn jump_to_exercise(&mut self, index: usize) {
+        let Some(target) = self.all_exercises.get(index).cloned() else {
+            return;
+        };
+        if !self.is_unlocked_in_course(&target) {
+            self.set_temp_status(format!(
+                "{} is locked - complete the exercises before it first",
+                target.title
+            ));
+            return;
+        }
+        let Some(folder) = target.path.parent() else {
+            return;
+        };
+
+        // Folders from the course root down to the target's folder
+        let mut ancestors: Vec<&Exercise> = self
+            .all_exercises
+            .iter()
+            .filter(|e| e.is_folder && folder.starts_with(&e.path))
+            .collect();
+        ancestors.sort_by_key(|e| e.path.components().count());
+        self.breadcrumb = ancestors.iter().map(|e| e.title.clone()).collect();
+
+        self.current_folder_path = folder.to_path_buf();
+        self.current_exercises =
+            Self::load_current_folder(&self.all_exercises, &self.current_folder_path);
+        self.selected_index = self
+            .current_exercises
+            .iter()
+            .position(|e| e.path == target.path)
+            .unwrap_or(0);
+        self.list_state = ListState::default();
+        self.list_state.select(Some(self.selected_index));
+        self.update_list_scroll();
+        self.show_readme();
+    }
+
     fn select_next(&mut self) {
         if self.current_exercises.is_empty() {
             return;
@@ -326,10 +456,9 @@ impl App {
                 self.display_mode = DisplayMode::Readme;
                 self.test_output_lines.clear();
                 self.last_test_result = None;
+                self.last_test_summary = None;
                 self.scroll_position = 0;
-                self.is_running_test = false;
-                self.output_receiver = None;
-                self.result_receiver = None;
+                self.cancel_current_test();
                 return;
             }
             next_index += 1;
@@ -355,10 +484,9 @@ impl App {
                     self.display_mode = DisplayMode::Readme;
                     self.test_output_lines.clear();
                     self.last_test_result = None;
+                    self.last_test_summary = None;
                     self.scroll_position = 0;
-                    self.is_running_test = false;
-                    self.output_receiver = None;
-                    self.result_receiver = None;
+                    self.cancel_current_test();
                     return;
                 }
 
@@ -423,47 +551,44 @@ impl App {
 
             self.is_running_test = true;
             self.running_exercise_id = Some(exercise_id.clone());
-            self.status_message =
-                String::from("Running tests... | ↑/↓ PgUp/PgDn Home/End - scroll, Esc - back");
+            self.status_message = format!(
+                "Running tests... | {}",
+                self.scroll_hints(&[(Action::Back, "cancel")])
+            );
             self.display_mode = DisplayMode::TestOutput;
             self.test_output_lines = vec![String::from("Running tests..."), String::new()];
             self.scroll_position = 0;
             self.setup_start_index = None; // Reset setup tracking for new test run
 
-            // Create channels for streaming output and result
+            // Create channels for streaming output, result and cancellation
             let (output_tx, output_rx) = mpsc::channel(100);
             let (result_tx, result_rx) = mpsc::channel(1);
+            let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
             self.output_receiver = Some(output_rx);
             self.result_receiver = Some(result_rx);
+            self.run_cancel_tx = Some(cancel_tx);
 
             // Spawn test runner in background
             let test_runner = self.test_runner.clone();
-            let db = self.database.clone();
 
-            tokio::spawn(async move {
-                let result = match test_runner
-                    .run_test_streaming(&exercise_clone, output_tx)
+            let task = tokio::spawn(async move {
+                let outcome = match test_runner
+                    .run_test_streaming(&exercise_clone, output_tx, &mut cancel_rx)
                     .await
                 {
-                    Ok(result) => {
-                        // Update database based on result
-                        match &result {
-                            TestResult::Passed => {
-                                let _ = db.mark_completed(&exercise_clone.id);
-                            }
-                            TestResult::Failed => {
-                                let _ = db.mark_attempted(&exercise_clone.id);
-                            }
-                            _ => {}
-                        }
-                        result
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        TestOutcome::new(TestResult::Error(format!("Failed to run test: {:#}", e)))
                     }
-                    Err(_) => TestResult::Error("Failed to run test".to_string()),
                 };
 
-                // Send result back to main thread
-                let _ = result_tx.send(result).await;
+                // Send result back to main thread, which records it only if the
+                // user is still waiting for it
+                if !matches!(outcome.result, TestResult::Cancelled) {
+                    let _ = result_tx.send(outcome).await;
+                }
             });
+            self.test_task = Some(task);
         }
         Ok(())
     }
@@ -509,32 +634,89 @@ impl App {
 
         // Check if test completed
         if let Some(ref mut result_rx) = self.result_receiver {
-            if let Ok(result) = result_rx.try_recv() {
-                // Test completed
+            if let Ok(outcome) = result_rx.try_recv() {
+                // Test completed. Leaving the output drops the receiver, so a
+                // cancelled run never gets here and is never recorded.
+                let mut save_error = None;
+                if let Some(id) = &self.running_exercise_id {
+                    let hint_used = self.hinted_exercises.remove(id);
+                    match self.database.record_outcome(id, &outcome, hint_used) {
+                        Ok(()) => self
+                            .progress
+                            .record(id, &outcome.result, &self.all_exercises),
+                        Err(e) => save_error = Some(e),
+                    }
+                }
+                let result = outcome.result;
                 self.last_test_result = Some(result.clone());
+                self.last_test_summary = outcome.summary;
                 self.is_running_test = false;
                 self.result_receiver = None;
+                self.run_cancel_tx = None;
+                self.test_task = None;
                 self.running_exercise_id = None;
 
+                let counts = self
+                    .last_test_summary
+                    .as_ref()
+                    .map(|summary| format!(" ({})", summary.counts_text()))
+                    .unwrap_or_default();
+
                 // Update status message based on result
                 if let Some(exercise) = self.get_selected_exercise() {
                     let title = &exercise.title;
                     match result {
                         TestResult::Passed => {
                             self.status_message = format!(
-                                "✓ {} passed! | p - extract to playground, ↑/↓ scroll, Enter - run again, Esc - back",
-                                title
+                                "✓ {} passed!{} | {}",
+                                title,
+                                counts,
+                                self.scroll_hints(&[
+                                    (Action::Playground, "extract to playground"),
+                                    (Action::Confirm, "run again"),
+                                    (Action::Back, "back"),
+                                ])
                             );
                         }
                         TestResult::Failed => {
-                            self.status_message = format!("✗ {} failed | ↑/↓ PgUp/PgDn Home/End - scroll, Enter - run again, h - hint, Esc - back", title);
+                            self.status_message = format!(
+                                "✗ {} failed{} | {}",
+                                title,
+                                counts,
+                                self.scroll_hints(&[
+                                    (Action::Confirm, "run again"),
+                                    (Action::Hint, "hint"),
+                                    (Action::Back, "back"),
+                                ])
+                            );
+                        }
+                        TestResult::Timeout(limit) => {
+                            self.status_message = format!(
+                                "⏱ {} timed out after {}s | Check for infinite loops, {}",
+                                title,
+                                limit.as_secs(),
+                                self.keymap.hints(&[
+                                    (Action::Confirm, "run again"),
+                                    (Action::Back, "back")
+                                ])
+                            );
+                        }
+                        TestResult::Cancelled => {
+                            self.status_message = self.default_status_message.clone();
                         }
                         TestResult::Error(err) => {
-                            self.status_message =
-                                format!("Error: {} | Enter - retry, Esc - back", err);
+                            self.status_message = format!(
+                                "Error: {} | {}",
+                                err,
+                                self.keymap
+                                    .hints(&[(Action::Confirm, "retry"), (Action::Back, "back")])
+                            );
                         }
                     }
                 }
+                if let Some(e) = save_error {
+                    self.set_status(format!("Failed to save the test result: {:#}", e));
+                }
             }
         }
 
@@ -548,14 +730,51 @@ impl App {
         self.check_run_all_progress();
     }
 
+    /// Cancel the running single-exercise test, killing its process tree.
+    /// The run's result is discarded and never recorded in the database.
+    fn cancel_current_test(&mut self) {
+        if let Some(cancel_tx) = self.run_cancel_tx.take() {
+            let _ = cancel_tx.try_send(());
+        }
+        if let Some(task) = self.test_task.take() {
+            // Give the task a moment to kill the command's process tree before
+            // aborting it, which would only kill the direct child
+            let abort = task.abort_handle();
+            tokio::spawn(async move {
+                if tokio::time::timeout(std::time::Duration::from_secs(2), task)
+                    .await
+                    .is_err()
+                {
+                    abort.abort();
+                }
+            });
+        }
+        self.output_receiver = None;
+        self.result_receiver = None;
+        self.is_running_test = false;
+        self.running_exercise_id = None;
+    }
+
+    /// Cancel every running test and wait briefly for their processes to be killed
+    async fn shutdown(&mut self) {
+        let tasks = [self.test_task.take(), self.run_all_task.take()];
+        self.cancel_current_test();
+        self.cancel_hint_generation();
+        if let Some(cancel_tx) = self.run_all_cancel_tx.take() {
+            let _ = cancel_tx.try_send(());
+        }
+        for task in tasks.into_iter().flatten() {
+            let _ = tokio::time::timeout(std::time::Duration::from_secs(2), task).await;
+        }
+    }
+
     fn show_readme(&mut self) {
         self.display_mode = DisplayMode::Readme;
         self.test_output_lines.clear();
         self.scroll_position = 0;
-        self.is_running_test = false;
-        self.output_receiver = None;
-        self.result_receiver = None;
+        self.cancel_current_test();
         self.last_test_result = None;
+        self.last_test_summary = None;
         self.running_exercise_id = None;
         self.status_message = self.default_status_message.clone();
         self.status_message_timestamp = None;
@@ -567,6 +786,25 @@ impl App {
         self.status_message_timestamp = Some(std::time::Instant::now());
     }
 
+    /// Status bar hints for a scrolling view, the scroll keys first
+    fn scroll_hints(&self, hints: &[(Action, &str)]) -> String {
+        format!(
+            "{}, {}",
+            self.keymap.scroll_hint(),
+            self.keymap.hints(hints)
+        )
+    }
+
+    /// Status bar hints while a tutor reply is on screen
+    fn reply_hints(&self) -> String {
+        self.scroll_hints(&[
+            (Action::FollowUp, "ask a follow-up"),
+            (Action::RegenerateHint, "regenerate"),
+            (Action::ChangeModel, "change model"),
+            (Action::Back, "back"),
+        ])
+    }
+
     /// Set a permanent status message (won't auto-hide)
     fn set_status(&mut self, message: String) {
         self.status_message = message;
@@ -584,39 +822,116 @@ impl App {
     }
 
     fn check_hint_generation(&mut self) {
-        // Check for new tokens
+        self.drain_hint_tokens();
+
+        // Check if generation is complete
+        let Some(ref mut complete_rx) = self.hint_complete_receiver else {
+            return;
+        };
+        let Ok(succeeded) = complete_rx.try_recv() else {
+            return;
+        };
+        // Tokens sent just before completion
+        self.drain_hint_tokens();
+        self.is_generating_hint = false;
+        self.hint_receiver = None;
+        self.hint_complete_receiver = None;
+        self.hint_task = None;
+
+        if !succeeded {
+            // Keep the error on screen; the unanswered question isn't saved
+            self.drop_unanswered_question();
+            self.status_message = format!(
+                "Hint failed | {}",
+                self.scroll_hints(&[
+                    (Action::Hint, "try again"),
+                    (Action::ChangeModel, "change model"),
+                    (Action::Back, "back"),
+                ])
+            );
+            return;
+        }
+
+        let reply = self.hint_text.take().unwrap_or_default();
+        if let (Some(exercise_id), Some((model, fingerprint)), true) = (
+            &self.hint_exercise_id,
+            &self.hint_cache_key,
+            self.hint_new_conversation,
+        ) {
+            let _ = self
+                .database
+                .cache_hint(exercise_id, model, fingerprint, &reply);
+        }
+        self.hint_conversation.push(ChatMessage::assistant(reply));
+        if let Some(exercise_id) = &self.hint_exercise_id {
+            let fingerprint = self.hint_cache_key.as_ref().map_or("", |(_, f)| f.as_str());
+            let saved = if self.hint_new_conversation {
+                self.database.start_hint_conversation(
+                    exercise_id,
+                    fingerprint,
+                    &self.hint_conversation,
+                )
+            } else {
+                let start = self.hint_conversation.len().saturating_sub(2);
+                self.database
+                    .append_hint_messages(exercise_id, &self.hint_conversation[start..])
+            };
+            if let Err(e) = saved {
+                self.set_status(format!("Failed to save hint conversation: {}", e));
+                return;
+            }
+        }
+        self.status_message = format!("Hint ready! | {}", self.reply_hints());
+    }
+
+    fn drain_hint_tokens(&mut self) {
         if let Some(ref mut rx) = self.hint_receiver {
             while let Ok(token) = rx.try_recv() {
-                // Append token to hint text (streaming)
-                if let Some(ref mut hint) = self.hint_text {
-                    hint.push_str(&token);
-                } else {
-                    self.hint_text = Some(token);
-                }
+                // Append token to the reply (streaming)
+                self.hint_text
+                    .get_or_insert_with(String::new)
+                    .push_str(&token);
             }
         }
+    }
 
-        // Check if generation is complete
-        if let Some(ref mut complete_rx) = self.hint_complete_receiver {
-            if complete_rx.try_recv().is_ok() {
-                self.is_generating_hint = false;
-                self.hint_complete_receiver = None;
-                self.status_message = String::from(
-                    "Hint ready! | ↑/↓ PgUp/PgDn Home/End - scroll, m - change model, Esc - back",
-                );
-            }
+    /// Forget a question whose reply was cancelled or failed
+    fn drop_unanswered_question(&mut self) {
+        if self
+            .hint_conversation
+            .last()
+            .is_some_and(|message| message.role == Role::User)
+        {
+            self.hint_conversation.pop();
         }
     }
 
+    /// Abort an in-flight hint generation, dropping the request to the provider.
+    /// Tokens received so far stay in the hint text.
+    fn cancel_hint_generation(&mut self) {
+        if let Some(task) = self.hint_task.take() {
+            task.abort();
+        }
+        if self.is_generating_hint {
+            self.drop_unanswered_question();
+        }
+        self.hint_receiver = None;
+        self.hint_complete_receiver = None;
+        self.is_generating_hint = false;
+        self.chat_input = None;
+    }
+
     fn show_test_output(&mut self) {
         self.display_mode = DisplayMode::TestOutput;
         self.scroll_position = 0;
         if let Some(_exercise) = self.get_selected_exercise() {
             if self.last_test_result.is_some() {
                 self.status_message =
-                    String::from("↑/↓ PgUp/PgDn Home/End - scroll, h - hint, Esc - back");
+                    self.scroll_hints(&[(Action::Hint, "hint"), (Action::Back, "back")]);
             } else {
-                self.status_message = String::from("Enter - run tests, Esc - back");
+                self.status_message = self
+                    .keymap
+                    .hints(&[(Action::Confirm, "run tests"), (Action::Back, "back")]);
             }
         }
     }
@@ -634,21 +949,45 @@ impl App {
                     self.playground_path = Some(playground_path.clone());
                     self.show_playground_success = true;
                     self.display_mode = DisplayMode::TestOutput;
-                    self.status_message = "✓ Extracted to ./playground | p - extract again, Enter - run test, Esc - back".to_string();
+                    self.status_message = format!(
+                        "✓ Extracted to ./playground | {}",
+                        self.keymap.hints(&[
+                            (Action::Playground, "extract again"),
+                            (Action::Confirm, "run test"),
+                            (Action::Back, "back"),
+                        ])
+                    );
                 }
                 Err(e) => {
                     self.display_mode = DisplayMode::TestOutput;
-                    self.status_message =
-                        format!("✗ Failed to extract playground: {} | Esc - back", e);
+                    self.status_message = format!(
+                        "✗ Failed to extract playground: {} | {}",
+                        e,
+                        self.keymap.hints(&[(Action::Back, "back")])
+                    );
                 }
             }
         }
         Ok(())
     }
 
+    fn hint_provider(&self) -> Result<Arc<dyn HintProvider>> {
+        hint::provider_from_config(&self.config)
+    }
+
     async fn fetch_available_models(&mut self) {
         self.display_mode = DisplayMode::ModelSelection;
-        self.status_message = String::from("Loading available models from Ollama...");
+        let provider = match self.hint_provider() {
+            Ok(provider) => provider,
+            Err(e) => {
+                self.available_models = vec![format!("Error: {:#}", e)];
+                self.model_list_state.select(Some(0));
+                self.status_message =
+                    format!("Press {} to go back", self.keymap.primary(Action::Back));
+                return;
+            }
+        };
+        self.status_message = format!("Loading available models from {}...", provider.name());
         self.available_models = vec![String::from("Loading...")];
         self.model_list_state.select(Some(0));
 
@@ -657,24 +996,27 @@ impl App {
         self.models_receiver = Some(models_rx);
 
         // Spawn model fetching in background
+        let refresh_key = self.keymap.primary(Action::ChangeModel);
         tokio::spawn(async move {
-            use ollama_rs::Ollama;
-
-            let ollama = Ollama::default();
-            match ollama.list_local_models().await {
-                Ok(models) => {
-                    let model_names: Vec<String> = models.iter().map(|m| m.name.clone()).collect();
-
+            match provider.list_models().await {
+                Ok(model_names) => {
                     if model_names.is_empty() {
-                        let _ = models_tx.send(vec![String::from("No models found. Please install a model with 'ollama pull <model-name>'")]).await;
+                        let _ = models_tx
+                            .send(vec![format!(
+                                "No models found on {}. Install or load a model, then press {} to refresh",
+                                provider.name(),
+                                refresh_key
+                            )])
+                            .await;
                     } else {
                         let _ = models_tx.send(model_names).await;
                     }
                 }
                 Err(_) => {
                     let _ = models_tx
-                        .send(vec![String::from(
-                            "Error: Cannot connect to Ollama. Make sure Ollama is running.",
+                        .send(vec![format!(
+                            "Error: Cannot connect to {}. Make sure it is running.",
+                            provider.name()
                         )])
                         .await;
                 }
@@ -693,9 +1035,17 @@ impl App {
                         || self.available_models[0].contains("Error:"))
                 {
                     // Error or no models
-                    self.status_message = String::from("Press Esc to go back");
+                    self.status_message =
+                        format!("Press {} to go back", self.keymap.primary(Action::Back));
                 } else {
-                    self.status_message = String::from("Select a model (↑/↓ to navigate, Enter to select, Esc to cancel, 'm' to refresh)");
+                    self.status_message = format!(
+                        "Select a model ({}/{} to navigate, {} to select, {} to cancel, {} to refresh)",
+                        self.keymap.primary(Action::PickerPrevious),
+                        self.keymap.primary(Action::PickerNext),
+                        self.keymap.primary(Action::Confirm),
+                        self.keymap.primary(Action::Back),
+                        self.keymap.primary(Action::ChangeModel),
+                    );
                     self.model_list_state.select(Some(0));
                 }
             }
@@ -729,8 +1079,10 @@ impl App {
             if let Some(model) = self.available_models.get(selected_idx) {
                 // Don't select if it's an error message
                 if !model.contains("No models found") && !model.contains("Error:") {
-                    self.config.set_model(model.clone())?;
-                    self.status_message = format!("Model '{}' selected and saved!", model);
+                    self.status_message = match self.config.set_model(model.clone()) {
+                        Ok(()) => format!("Model '{}' selected and saved!", model),
+                        Err(e) => format!("Model '{}' selected but not saved: {:#}", model, e),
+                    };
                 }
             }
         }
@@ -743,7 +1095,13 @@ impl App {
             self.editor_list_state.select(Some(0));
         }
         self.display_mode = DisplayMode::EditorSelection;
-        self.set_status(String::from("↑/↓ - navigate, Enter - select, Esc - cancel"));
+        self.set_status(format!(
+            "{}/{} - navigate, {}",
+            self.keymap.primary(Action::PickerPrevious),
+            self.keymap.primary(Action::PickerNext),
+            self.keymap
+                .hints(&[(Action::Confirm, "select"), (Action::Back, "cancel")])
+        ));
     }
 
     fn select_next_editor(&mut self) {
@@ -810,7 +1168,91 @@ impl App {
         }
     }
 
-    async fn check_model_and_generate_hint(&mut self) -> Result<()> {
+    /// Reveal the exercise's authored hints one level at a time, falling back to the
+    /// AI provider once they are exhausted. From the test output this reopens the
+    /// hints already revealed; from the hint view it moves on to the next level.
+    async fn request_hint(&mut self, next_level: bool) -> Result<()> {
+        let Some(exercise) = self.get_selected_exercise() else {
+            return Ok(());
+        };
+        let exercise_id = exercise.id.clone();
+        let authored = exercise.metadata.hints.clone().unwrap_or_default();
+
+        let revealed = self
+            .database
+            .get_hint_levels_used(&exercise_id)
+            .unwrap_or(0)
+            .min(authored.len());
+        let level = if next_level || revealed == 0 {
+            revealed + 1
+        } else {
+            revealed
+        };
+        if level > authored.len() {
+            // Collecting the context walks the exercise and runs git, so do it once
+            let Some(hint_prompt) = self.hint_prompt() else {
+                return Ok(());
+            };
+            // Reopen the saved conversation if the code and test output haven't changed
+            if !next_level {
+                let saved_fingerprint = self
+                    .database
+                    .get_hint_conversation_fingerprint(&exercise_id)
+                    .unwrap_or_default();
+                let conversation = self
+                    .database
+                    .get_hint_conversation(&exercise_id)
+                    .unwrap_or_default();
+                if saved_fingerprint.as_ref() == Some(&hint_prompt.fingerprint)
+                    && conversation.iter().any(|m| m.role == Role::Assistant)
+                {
+                    self.cancel_hint_generation();
+                    self.revealed_hints = authored;
+                    self.hint_conversation = conversation;
+                    self.hint_exercise_id = Some(exercise_id);
+                    self.hint_text = None;
+                    self.display_mode = DisplayMode::Hint;
+                    self.scroll_position = 0;
+                    self.set_status(self.reply_hints());
+                    return Ok(());
+                }
+            }
+            return self.check_model_and_generate_hint(hint_prompt).await;
+        }
+
+        let _ = self.database.record_hint_level(&exercise_id, level);
+        self.hinted_exercises.insert(exercise_id);
+        self.cancel_hint_generation();
+        self.revealed_hints = authored[..level].to_vec();
+        self.hint_conversation.clear();
+        self.hint_text = None;
+        self.display_mode = DisplayMode::Hint;
+        self.scroll_position = 0;
+
+        let next = if level < authored.len() {
+            "next hint"
+        } else {
+            "ask AI"
+        };
+        self.set_status(format!(
+            "Hint {} of {} | {}",
+            level,
+            authored.len(),
+            self.scroll_hints(&[(Action::Hint, next), (Action::Back, "back")])
+        ));
+        Ok(())
+    }
+
+    async fn check_model_and_generate_hint(&mut self, hint_prompt: HintPrompt) -> Result<()> {
+        // A cached hint doesn't need the provider, so it works offline too
+        if let Some(model) = self.config.get_model().map(String::from) {
+            if self.has_cached_hint(&model, &hint_prompt.fingerprint) {
+                return self
+                    .generate_hint_with_model(model, false, hint_prompt)
+                    .await;
+            }
+        }
+
         // Check if model is configured
         if let Some(model) = self.config.get_model() {
             // Check if the configured model is still available
@@ -819,14 +1261,17 @@ impl App {
             self.available_models = vec![String::from("Checking...")];
 
             let model_clone = model.to_string();
+            let Ok(provider) = self.hint_provider() else {
+                // Let the model list report the configuration error
+                self.fetch_available_models().await;
+                return Ok(());
+            };
             let (check_tx, mut check_rx) = mpsc::channel(1);
 
             tokio::spawn(async move {
-                use ollama_rs::Ollama;
-                let ollama = Ollama::default();
-                match ollama.list_local_models().await {
+                match provider.list_models().await {
                     Ok(models) => {
-                        let model_exists = models.iter().any(|m| m.name == model_clone);
+                        let model_exists = models.contains(&model_clone);
                         let _ = check_tx.send(model_exists).await;
                     }
                     Err(_) => {
@@ -839,7 +1284,8 @@ impl App {
             if let Some(exists) = check_rx.recv().await {
                 if exists {
                     // Model exists, generate hint
-                    self.generate_hint_with_model(model.to_string()).await?;
+                    self.generate_hint_with_model(model.to_string(), false, hint_prompt)
+                        .await?;
                 } else {
                     // Model doesn't exist, show model selection
                     self.fetch_available_models().await;
@@ -854,110 +1300,306 @@ impl App {
         Ok(())
     }
 
-    async fn generate_hint_with_model(&mut self, model: String) -> Result<()> {
-        if let Some(exercise) = self.get_selected_exercise() {
-            let exercise_title = exercise.title.clone();
-            let exercise_description = exercise.description.clone();
-            let test_output = self.test_output_lines.join("\n");
-
-            // Collect all context files (auto-discover or from metadata)
-            let context_files = exercise
-                .collect_context_files()
-                .unwrap_or_else(|_| Vec::new());
-
-            // Build context section with all files
-            let mut context_section = String::new();
-            if context_files.is_empty() {
-                // Fallback to just the exercise file if collection failed
-                let exercise_code = std::fs::read_to_string(exercise.path.join("exercise.js"))
-                    .unwrap_or_else(|_| String::from("Could not read exercise file"));
-                context_section.push_str("Current code:\n```javascript\n");
-                context_section.push_str(&exercise_code);
-                context_section.push_str("\n```\n");
-            } else if context_files.len() == 1 {
-                // Single file - simple format
-                context_section.push_str("Current code:\n```javascript\n");
-                context_section.push_str(&context_files[0].1);
-                context_section.push_str("\n```\n");
-            } else {
-                // Multiple files - show each with filename
-                context_section.push_str("Current code files:\n\n");
-                for (path, content) in &context_files {
-                    let filename = path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown");
-                    context_section.push_str(&format!(
-                        "File: {}\n```javascript\n{}\n```\n\n",
-                        filename, content
-                    ));
-                }
+    /// The hint prompt for the selected exercise and a fingerprint of its code
+    /// and test output
+    fn hint_prompt(&self) -> Option<HintPrompt> {
+        let exercise = self.get_selected_exercise()?;
+        let test_output = self.test_output_lines.join("\n");
+
+        // Collect all context files (auto-discover or from metadata)
+        let context_files = exercise
+            .collect_context_files()
+            .unwrap_or_else(|_| Vec::new());
+        let context = HintContext {
+            exercise,
+            files: &context_files,
+            test_output: &test_output,
+        };
+        Some(HintPrompt {
+            prompt: prompt::build_hint_prompt(self.course.hint_prompt.as_deref(), &context),
+            fingerprint: context.fingerprint(),
+        })
+    }
+
+    fn has_cached_hint(&self, model: &str, fingerprint: &str) -> bool {
+        let Some(exercise) = self.get_selected_exercise() else {
+            return false;
+        };
+        matches!(
+            self.database
+                .get_cached_hint(&exercise.id, model, fingerprint),
+            Ok(Some(_))
+        )
+    }
+
+    /// Regenerate the AI hint, skipping the cache
+    async fn regenerate_hint(&mut self) -> Result<()> {
+        let Some(hint_prompt) = self.hint_prompt() else {
+            return Ok(());
+        };
+        match self.config.get_model() {
+            Some(model) => {
+                self.generate_hint_with_model(model.to_string(), true, hint_prompt)
+                    .await
             }
+            None => self.check_model_and_generate_hint(hint_prompt).await,
+        }
+    }
 
-            self.is_generating_hint = true;
+    /// Show the AI hint for the selected exercise, from the cache unless `regenerate`
+    async fn generate_hint_with_model(
+        &mut self,
+        model: String,
+        regenerate: bool,
+        hint_prompt: HintPrompt,
+    ) -> Result<()> {
+        if let Some(exercise) = self.get_selected_exercise() {
+            let exercise_id = exercise.id.clone();
+            let authored = exercise.metadata.hints.clone().unwrap_or_default();
+            let HintPrompt {
+                prompt,
+                fingerprint,
+            } = hint_prompt;
+
+            self.cancel_hint_generation();
             self.display_mode = DisplayMode::Hint;
-            self.hint_text = Some(String::new());
             self.scroll_position = 0;
-            self.status_message = format!("Generating hint with {}... | ↑/↓ PgUp/PgDn Home/End - scroll, m - change model, Esc - back", model);
 
-            let (hint_tx, hint_rx) = mpsc::channel(100);
-            let (complete_tx, complete_rx) = mpsc::channel(1);
-            self.hint_receiver = Some(hint_rx);
-            self.hint_complete_receiver = Some(complete_rx);
-
-            tokio::spawn(async move {
-                use ollama_rs::generation::completion::request::GenerationRequest;
-                use ollama_rs::Ollama;
-
-                let prompt = format!(
-                    r#"You are a helpful programming tutor. A student is working on the following exercise:
-
-Exercise: {}
-Description: {}
+            // Keep the authored hints above the AI one
+            let revealed = self
+                .database
+                .get_hint_levels_used(&exercise_id)
+                .unwrap_or(0)
+                .min(authored.len());
+            self.revealed_hints = authored[..revealed].to_vec();
+
+            self.hint_conversation.clear();
+            self.hint_text = None;
+
+            if !regenerate {
+                if let Ok(Some(hint)) =
+                    self.database
+                        .get_cached_hint(&exercise_id, &model, &fingerprint)
+                {
+                    let _ = self
+                        .database
+                        .record_hint_level(&exercise_id, authored.len() + 1);
+                    self.hinted_exercises.insert(exercise_id.clone());
+                    self.hint_conversation =
+                        vec![ChatMessage::user(prompt), ChatMessage::assistant(hint)];
+                    let _ = self.database.start_hint_conversation(
+                        &exercise_id,
+                        &fingerprint,
+                        &self.hint_conversation,
+                    );
+                    self.hint_exercise_id = Some(exercise_id);
+                    self.status_message =
+                        format!("Cached hint from {} | {}", model, self.reply_hints());
+                    return Ok(());
+                }
+            }
 
-{}
+            let provider = match self.hint_provider() {
+                Ok(provider) => provider,
+                Err(e) => {
+                    self.hint_text = Some(format!("Failed to generate hint: {:#}", e));
+                    self.status_message = format!(
+                        "Check the hint settings with 'learnp config' | {}",
+                        self.keymap.hints(&[
+                            (Action::ChangeModel, "change model"),
+                            (Action::Back, "back"),
+                        ])
+                    );
+                    return Ok(());
+                }
+            };
 
-Test output showing failures:
-```
-{}
-```
+            // The AI hint is the level after the authored ones
+            let _ = self
+                .database
+                .record_hint_level(&exercise_id, authored.len() + 1);
+            self.hinted_exercises.insert(exercise_id.clone());
+            self.status_message = format!(
+                "Generating hint with {}... | {}",
+                model,
+                self.scroll_hints(&[
+                    (Action::ChangeModel, "change model"),
+                    (Action::Back, "cancel"),
+                ])
+            );
 
-Provide a helpful hint (not the full solution) to guide them toward fixing the issue. Be encouraging and educational.
+            self.hint_conversation.push(ChatMessage::user(prompt));
+            self.hint_new_conversation = true;
+            self.hint_cache_key = Some((model.clone(), fingerprint));
+            self.stream_hint_reply(provider, model, exercise_id);
+        }
+        Ok(())
+    }
 
-Hint:"#,
-                    exercise_title, exercise_description, context_section, test_output
-                );
+    /// Send a follow-up question about the current hint conversation
+    fn send_follow_up(&mut self, question: String) {
+        let Some(exercise_id) = self.hint_exercise_id.clone() else {
+            return;
+        };
+        let Some(model) = self.config.get_model().map(String::from) else {
+            self.set_status(format!(
+                "No model selected | {}",
+                self.keymap.hints(&[
+                    (Action::ChangeModel, "change model"),
+                    (Action::Back, "back"),
+                ])
+            ));
+            return;
+        };
+        let provider = match self.hint_provider() {
+            Ok(provider) => provider,
+            Err(e) => {
+                self.set_status(format!(
+                    "Failed to ask the tutor: {:#} | {}",
+                    e,
+                    self.keymap.hints(&[(Action::Back, "back")])
+                ));
+                return;
+            }
+        };
 
-                let ollama = Ollama::default();
-                let request = GenerationRequest::new(model.clone(), prompt);
+        self.hinted_exercises.insert(exercise_id.clone());
+        self.hint_conversation.push(ChatMessage::user(question));
+        self.hint_new_conversation = false;
+        self.status_message = format!(
+            "Asking {}... | {}",
+            model,
+            self.scroll_hints(&[(Action::Back, "cancel")])
+        );
+        self.stream_hint_reply(provider, model, exercise_id);
+        self.scroll_to_bottom();
+    }
 
-                // Use the generate method and send response in chunks (simulating streaming)
-                match ollama.generate(request).await {
-                    Ok(response) => {
-                        // Simulate streaming by sending character chunks to preserve formatting
-                        let chars: Vec<char> = response.response.chars().collect();
-                        let chunk_size = 3; // Send 3 characters at a time (simulates token streaming)
+    /// Stream the provider's reply to `hint_conversation` into `hint_text`
+    fn stream_hint_reply(
+        &mut self,
+        provider: Arc<dyn HintProvider>,
+        model: String,
+        exercise_id: String,
+    ) {
+        self.is_generating_hint = true;
+        self.hint_exercise_id = Some(exercise_id);
+        self.hint_text = Some(String::new());
+
+        let (hint_tx, hint_rx) = mpsc::channel(100);
+        let (complete_tx, complete_rx) = mpsc::channel(1);
+        self.hint_receiver = Some(hint_rx);
+        self.hint_complete_receiver = Some(complete_rx);
+
+        let messages = self.hint_conversation.clone();
+        let task = tokio::spawn(async move {
+            use futures_util::StreamExt;
+
+            let mut stream = match provider.chat(model.clone(), messages).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    let error_msg = format!("Failed to generate hint: {:#}. Make sure {} is running and the '{}' model is available.", e, provider.name(), model);
+                    let _ = hint_tx.send(error_msg).await;
+                    let _ = complete_tx.send(false).await;
+                    return;
+                }
+            };
 
-                        for chunk in chars.chunks(chunk_size) {
-                            let chunk_str: String = chunk.iter().collect();
-                            if (hint_tx.send(chunk_str).await).is_err() {
-                                break;
-                            }
-                            // Small delay to simulate streaming (adjust for smoother/faster streaming)
-                            tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+            // Forward tokens as the provider produces them
+            while let Some(token) = stream.next().await {
+                match token {
+                    Ok(token) => {
+                        if hint_tx.send(token).await.is_err() {
+                            return;
                         }
-                        // Signal completion
-                        let _ = complete_tx.send(()).await;
                     }
                     Err(e) => {
-                        let error_msg = format!("Failed to generate hint: {}. Make sure Ollama is running and the '{}' model is available.", e, model);
-                        let _ = hint_tx.send(error_msg).await;
-                        let _ = complete_tx.send(()).await;
+                        let _ = hint_tx
+                            .send(format!("\n\nHint generation interrupted: {:#}", e))
+                            .await;
+                        let _ = complete_tx.send(false).await;
+                        return;
                     }
                 }
-            });
+            }
+            let _ = complete_tx.send(true).await;
+        });
+        self.hint_task = Some(task);
+    }
+
+    /// Handle a key press while typing a follow-up question
+    fn handle_chat_input(&mut self, key: event::KeyEvent) {
+        let Some(input) = self.chat_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let question = input.trim().to_string();
+                self.chat_input = None;
+                if !question.is_empty() {
+                    self.send_follow_up(question);
+                }
+            }
+            KeyCode::Esc => {
+                self.chat_input = None;
+                self.status_message = self.reply_hints();
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                input.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Start typing a follow-up question once the tutor has answered
+    fn start_chat_input(&mut self) {
+        if self.is_generating_hint
+            || !self
+                .hint_conversation
+                .iter()
+                .any(|m| m.role == Role::Assistant)
+        {
+            return;
+        }
+        self.chat_input = Some(String::new());
+        self.set_status(String::from(
+            "Type your question | Enter - send, Esc - cancel",
+        ));
+        self.scroll_to_bottom();
+    }
+
+    /// The selected exercise's README, rendered from Markdown, from line `skip` on
+    fn readme_lines(&self, skip: usize) -> Vec<Line<'static>> {
+        self.rendered_readme()
+            .map(|lines| lines.iter().skip(skip).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn readme_line_count(&self) -> usize {
+        self.rendered_readme().map_or(0, |lines| lines.len())
+    }
+
+    fn rendered_readme(&self) -> Option<Ref<'_, Vec<Line<'static>>>> {
+        let exercise = self.get_selected_exercise()?;
+        self.readme_cache
+            .get(&exercise.readme_file, |readme| markdown::render(&readme))
+    }
+
+    /// A finished tutor reply rendered as Markdown, reusing the last rendering
+    /// while the message at `index` is unchanged
+    fn rendered_reply(&self, index: usize, content: &str) -> Vec<Line<'static>> {
+        let mut cache = self.rendered_replies.borrow_mut();
+        match cache.get(&index) {
+            Some((cached, lines)) if cached == content => lines.clone(),
+            _ => {
+                let lines = markdown::render(content);
+                cache.insert(index, (content.to_string(), lines.clone()));
+                lines
+            }
         }
-        Ok(())
     }
 
     fn scroll_to_top(&mut self) {
@@ -966,29 +1608,9 @@ Hint:"#,
 
     fn scroll_to_bottom(&mut self) {
         let max_scroll = match self.display_mode {
-            DisplayMode::Hint => {
-                self.hint_text
-                    .as_ref()
-                    .map(|h| h.lines().count() + 4) // +4 for header lines
-                    .unwrap_or(0)
-                    .saturating_sub(1)
-            }
-            DisplayMode::ReadmeFocused => {
-                // Calculate README line count
-                if let Some(exercise) = self.get_selected_exercise() {
-                    if exercise.readme_file.exists() {
-                        if let Ok(readme) = std::fs::read_to_string(&exercise.readme_file) {
-                            readme.lines().count().saturating_sub(1)
-                        } else {
-                            0
-                        }
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            }
+            DisplayMode::Hint => hint_view_lines(self).len().saturating_sub(1),
+            DisplayMode::ReadmeFocused => self.readme_line_count().saturating_sub(1),
+            DisplayMode::Help => help_lines(&self.keymap).len().saturating_sub(1),
             _ => self.test_output_lines.len().saturating_sub(1),
         };
         self.scroll_position = max_scroll;
@@ -996,30 +1618,10 @@ Hint:"#,
 
     fn apply_scroll_delta(&mut self, delta: i32) {
         let max_scroll = match self.display_mode {
-            DisplayMode::Hint => {
-                self.hint_text
-                    .as_ref()
-                    .map(|h| h.lines().count() + 4) // +4 for header lines
-                    .unwrap_or(0)
-                    .saturating_sub(1)
-            }
+            DisplayMode::Hint => hint_view_lines(self).len().saturating_sub(1),
             DisplayMode::RunAllTests => self.run_all_output.len().saturating_sub(1),
-            DisplayMode::ReadmeFocused => {
-                // Calculate README line count
-                if let Some(exercise) = self.get_selected_exercise() {
-                    if exercise.readme_file.exists() {
-                        if let Ok(readme) = std::fs::read_to_string(&exercise.readme_file) {
-                            readme.lines().count().saturating_sub(1)
-                        } else {
-                            0
-                        }
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                }
-            }
+            DisplayMode::ReadmeFocused => self.readme_line_count().saturating_sub(1),
+            DisplayMode::Help => help_lines(&self.keymap).len().saturating_sub(1),
             _ => self.test_output_lines.len().saturating_sub(1),
         };
 
@@ -1048,8 +1650,12 @@ Hint:"#,
             .collect();
         self.run_all_current_index = 0;
         self.run_all_output = vec![String::from("Starting all tests..."), String::new()];
+        self.run_all_case_totals = (0, 0, 0);
 
-        self.status_message = String::from("Running all tests... | Esc - cancel");
+        self.status_message = format!(
+            "Running all tests... | {}",
+            self.keymap.hints(&[(Action::Back, "cancel")])
+        );
 
         // Create channels for progress updates and cancellation
         let (progress_tx, progress_rx) = mpsc::channel(10);
@@ -1060,17 +1666,15 @@ Hint:"#,
 
         // Clone data needed for the background task
         let test_runner = self.test_runner.clone();
-        let db = self.database.clone();
 
         // Spawn background task to run all tests sequentially
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             for (index, exercise) in exercises_only.iter().enumerate() {
                 // Check for cancellation
                 if cancel_rx.try_recv().is_ok() {
                     break;
                 }
 
-                let exercise_id = exercise.id.clone();
                 let exercise_clone = exercise.clone();
 
                 // Create a channel for this individual test's output
@@ -1084,35 +1688,30 @@ Hint:"#,
                 });
 
                 // Run the test
-                let result = match test_runner
-                    .run_test_streaming(&exercise_clone, output_tx)
+                let outcome = match test_runner
+                    .run_test_streaming(&exercise_clone, output_tx, &mut cancel_rx)
                     .await
                 {
-                    Ok(result) => {
-                        // Update database based on result
-                        match &result {
-                            TestResult::Passed => {
-                                let _ = db.mark_completed(&exercise_id);
-                            }
-                            TestResult::Failed => {
-                                let _ = db.mark_attempted(&exercise_id);
-                            }
-                            _ => {}
-                        }
-                        result
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        TestOutcome::new(TestResult::Error(format!("Failed to run test: {:#}", e)))
                     }
-                    Err(_) => TestResult::Error("Failed to run test".to_string()),
                 };
 
+                if matches!(outcome.result, TestResult::Cancelled) {
+                    break;
+                }
+
                 // Wait for drain task to finish
                 let _ = drain_handle.await;
 
                 // Send progress update
-                if progress_tx.send((index, result)).await.is_err() {
+                if progress_tx.send((index, outcome)).await.is_err() {
                     break;
                 }
             }
         });
+        self.run_all_task = Some(task);
 
         Ok(())
     }
@@ -1122,22 +1721,57 @@ Hint:"#,
 
         if let Some(ref mut rx) = self.run_all_receiver {
             // Check for progress updates
-            while let Ok((index, result)) = rx.try_recv() {
+            while let Ok((index, outcome)) = rx.try_recv() {
                 if index < self.run_all_progress.len() {
+                    // Saved here rather than in the task so progress.db and the
+                    // progress cache always agree
+                    let exercise_id = &self.run_all_progress[index].0;
+                    let save_error =
+                        match self.database.record_outcome(exercise_id, &outcome, false) {
+                            Ok(()) => {
+                                self.progress.record(
+                                    exercise_id,
+                                    &outcome.result,
+                                    &self.all_exercises,
+                                );
+                                None
+                            }
+                            Err(e) => Some(e),
+                        };
+                    let result = outcome.result;
                     self.run_all_progress[index].1 = Some(result.clone());
                     self.run_all_current_index = index + 1;
 
+                    if let Some(ref summary) = outcome.summary {
+                        self.run_all_case_totals.0 += summary.passed;
+                        self.run_all_case_totals.1 += summary.failed;
+                        self.run_all_case_totals.2 += summary.skipped;
+                    }
+
                     // Add to output
                     let exercise_id = &self.run_all_progress[index].0;
                     let exercise = self.all_exercises.iter().find(|e| &e.id == exercise_id);
                     let title = exercise.map(|e| e.title.as_str()).unwrap_or(exercise_id);
+                    let counts = outcome
+                        .summary
+                        .as_ref()
+                        .map(|summary| format!(" ({})", summary.counts_text()))
+                        .unwrap_or_default();
 
                     let status_line = match result {
-                        TestResult::Passed => format!("✓ {} - PASSED", title),
-                        TestResult::Failed => format!("✗ {} - FAILED", title),
+                        TestResult::Passed => format!("✓ {} - PASSED{}", title, counts),
+                        TestResult::Failed => format!("✗ {} - FAILED{}", title, counts),
                         TestResult::Error(ref err) => format!("✗ {} - ERROR: {}", title, err),
+                        TestResult::Timeout(limit) => {
+                            format!("⏱ {} - TIMEOUT after {}s", title, limit.as_secs())
+                        }
+                        TestResult::Cancelled => format!("- {} - CANCELLED", title),
                     };
                     self.run_all_output.push(status_line);
+                    if let Some(e) = save_error {
+                        self.run_all_output
+                            .push(format!("  Failed to save the result: {:#}", e));
+                    }
                 }
 
                 // Check if all tests completed
@@ -1152,6 +1786,7 @@ Hint:"#,
             self.is_running_all_tests = false;
             self.run_all_receiver = None;
             self.run_all_cancel_tx = None;
+            self.run_all_task = None;
 
             // Calculate summary
             let total = self.run_all_progress.len();
@@ -1170,19 +1805,32 @@ Hint:"#,
                 .iter()
                 .filter(|(_, r)| matches!(r, Some(TestResult::Error(_))))
                 .count();
+            let timeouts = self
+                .run_all_progress
+                .iter()
+                .filter(|(_, r)| matches!(r, Some(TestResult::Timeout(_))))
+                .count();
 
             self.run_all_output.push(String::new());
             self.run_all_output.push("─".repeat(50));
             self.run_all_output.push(String::new());
             self.run_all_output.push(format!(
-                "Total: {} | Passed: {} | Failed: {} | Errors: {}",
-                total, passed, failed, errors
+                "Total: {} | Passed: {} | Failed: {} | Errors: {} | Timeouts: {}",
+                total, passed, failed, errors, timeouts
             ));
+            let (cases_passed, cases_failed, cases_skipped) = self.run_all_case_totals;
+            if cases_passed + cases_failed + cases_skipped > 0 {
+                self.run_all_output.push(format!(
+                    "Test cases: {} passed | {} failed | {} skipped",
+                    cases_passed, cases_failed, cases_skipped
+                ));
+            }
 
+            let back = self.keymap.hints(&[(Action::Back, "back")]);
             if passed == total {
-                self.status_message = String::from("✓ All tests passed! | Esc - back");
+                self.status_message = format!("✓ All tests passed! | {}", back);
             } else {
-                self.status_message = String::from("Some tests failed. | Esc - back");
+                self.status_message = format!("Some tests failed. | {}", back);
             }
         }
     }
@@ -1191,8 +1839,11 @@ Hint:"#,
         if let Some(cancel_tx) = self.run_all_cancel_tx.take() {
             let _ = cancel_tx.try_send(());
         }
+        // Record the exercises that finished before the cancel
+        self.check_run_all_progress();
         self.is_running_all_tests = false;
         self.run_all_receiver = None;
+        self.run_all_task = None;
         self.show_readme();
     }
 }
@@ -1207,6 +1858,7 @@ pub async fn run_app(course_path: PathBuf, unblock_all: bool) -> Result<()> {
 
     let mut app = App::new(course_path, unblock_all)?;
     let res = run_app_loop(&mut terminal, &mut app).await;
+    app.shutdown().await;
 
     // Restore terminal
     disable_raw_mode()?;
@@ -1252,6 +1904,7 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                                 | DisplayMode::Hint
                                 | DisplayMode::RunAllTests
                                 | DisplayMode::ReadmeFocused
+                                | DisplayMode::Help
                         ) {
                             match mouse.kind {
                                 MouseEventKind::ScrollDown => {
@@ -1264,165 +1917,73 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                             }
                         }
                     }
+                    Event::Key(key)
+                        if key.kind == KeyEventKind::Press && app.chat_input.is_some() =>
+                    {
+                        app.handle_chat_input(key);
+                        scroll_delta = 0;
+                    }
+                    Event::Key(key)
+                        if key.kind == KeyEventKind::Press
+                            && matches!(app.display_mode, DisplayMode::Search) =>
+                    {
+                        app.handle_search_input(key);
+                        scroll_delta = 0;
+                    }
                     Event::Key(key) if key.kind == KeyEventKind::Press => {
-                        match key.code {
-                            KeyCode::Char('q') => {
+                        let Some(action) = app.keymap.action(&key, app.display_mode) else {
+                            continue;
+                        };
+                        match action {
+                            Action::Quit => {
                                 should_quit = true;
                                 break;
                             }
-                            KeyCode::Down if matches!(app.display_mode, DisplayMode::Readme) => {
-                                app.select_next();
-                            }
-                            KeyCode::Up if matches!(app.display_mode, DisplayMode::Readme) => {
-                                app.select_previous();
-                            }
-                            KeyCode::Char('j')
-                                if matches!(app.display_mode, DisplayMode::Readme) =>
-                            {
-                                app.select_next();
-                            }
-                            KeyCode::Char('k')
-                                if matches!(app.display_mode, DisplayMode::Readme) =>
-                            {
-                                app.select_previous();
-                            }
-                            // Folder navigation - enter folder
-                            KeyCode::Right if matches!(app.display_mode, DisplayMode::Readme) => {
-                                app.enter_folder();
-                            }
-                            KeyCode::Char('l')
-                                if matches!(app.display_mode, DisplayMode::Readme) =>
-                            {
-                                app.enter_folder();
-                            }
-                            // Folder navigation - go back
-                            KeyCode::Left if matches!(app.display_mode, DisplayMode::Readme) => {
-                                app.go_back_folder();
-                            }
-                            KeyCode::Char('h')
-                                if matches!(app.display_mode, DisplayMode::Readme) =>
-                            {
-                                app.go_back_folder();
-                            }
-                            KeyCode::Backspace if matches!(app.display_mode, DisplayMode::Readme) => {
-                                app.go_back_folder();
-                            }
-                            // Model selection navigation
-                            KeyCode::Down
-                                if matches!(app.display_mode, DisplayMode::ModelSelection) =>
-                            {
-                                app.select_next_model();
-                            }
-                            KeyCode::Up
-                                if matches!(app.display_mode, DisplayMode::ModelSelection) =>
-                            {
-                                app.select_previous_model();
-                            }
-                            // Editor selection navigation
-                            KeyCode::Down
-                                if matches!(app.display_mode, DisplayMode::EditorSelection) =>
-                            {
-                                app.select_next_editor();
-                            }
-                            KeyCode::Up
-                                if matches!(app.display_mode, DisplayMode::EditorSelection) =>
-                            {
-                                app.select_previous_editor();
-                            }
-                            // Batch scrolling in test output, hint, and run-all modes
-                            KeyCode::Down
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::TestOutput
-                                        | DisplayMode::Hint
-                                        | DisplayMode::RunAllTests
-                                        | DisplayMode::ReadmeFocused
-                                ) =>
-                            {
-                                scroll_delta += 1;
-                            }
-                            KeyCode::Up
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::TestOutput
-                                        | DisplayMode::Hint
-                                        | DisplayMode::RunAllTests
-                                        | DisplayMode::ReadmeFocused
-                                ) =>
-                            {
-                                scroll_delta -= 1;
-                            }
-                            KeyCode::PageDown => {
-                                scroll_delta += 10;
-                            }
-                            KeyCode::PageUp => {
-                                scroll_delta -= 10;
-                            }
-                            // Vim-style scrolling (j/k for down/up in test/hint/run-all modes)
-                            KeyCode::Char('j')
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::TestOutput
-                                        | DisplayMode::Hint
-                                        | DisplayMode::RunAllTests
-                                        | DisplayMode::ReadmeFocused
-                                ) =>
-                            {
-                                scroll_delta += 1;
-                            }
-                            KeyCode::Char('k')
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::TestOutput
-                                        | DisplayMode::Hint
-                                        | DisplayMode::RunAllTests
-                                        | DisplayMode::ReadmeFocused
-                                ) =>
-                            {
-                                scroll_delta -= 1;
-                            }
-                            // Vim-style page scrolling (Ctrl+d/u for page down/up)
-                            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                scroll_delta += 10;
-                            }
-                            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                                scroll_delta -= 10;
-                            }
-                            // Vim-style top/bottom (g/G for top/bottom)
-                            KeyCode::Char('g')
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::TestOutput
-                                        | DisplayMode::Hint
-                                        | DisplayMode::RunAllTests
-                                        | DisplayMode::ReadmeFocused
-                                ) =>
-                            {
-                                app.scroll_to_top();
+                            Action::Help => {
+                                app.toggle_help();
                                 scroll_delta = 0;
                             }
-                            KeyCode::Char('G')
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::TestOutput
-                                        | DisplayMode::Hint
-                                        | DisplayMode::RunAllTests
-                                        | DisplayMode::ReadmeFocused
-                                ) =>
-                            {
-                                app.scroll_to_bottom();
+                            Action::SelectNext => app.select_next(),
+                            Action::SelectPrevious => app.select_previous(),
+                            // Search the whole course
+                            Action::Search => {
+                                app.open_search();
                                 scroll_delta = 0;
                             }
-                            KeyCode::Home => {
+                            Action::EnterFolder => app.enter_folder(),
+                            Action::LeaveFolder => app.go_back_folder(),
+                            Action::PickerNext => {
+                                if app.display_mode == DisplayMode::ModelSelection {
+                                    app.select_next_model();
+                                } else {
+                                    app.select_next_editor();
+                                }
+                            }
+                            Action::PickerPrevious => {
+                                if app.display_mode == DisplayMode::ModelSelection {
+                                    app.select_previous_model();
+                                } else {
+                                    app.select_previous_editor();
+                                }
+                            }
+                            // Batched scrolling in the scrollable views
+                            Action::ScrollDown => scroll_delta += 1,
+                            Action::ScrollUp => scroll_delta -= 1,
+                            Action::PageDown => scroll_delta += 10,
+                            Action::PageUp => scroll_delta -= 10,
+                            Action::ScrollTop => {
                                 app.scroll_to_top();
                                 scroll_delta = 0;
                             }
-                            KeyCode::End => {
+                            Action::ScrollBottom => {
                                 app.scroll_to_bottom();
                                 scroll_delta = 0;
                             }
-                            KeyCode::Esc => {
-                                if matches!(app.display_mode, DisplayMode::RunAllTests) {
+                            Action::Back => {
+                                if matches!(app.display_mode, DisplayMode::Help) {
+                                    app.toggle_help();
+                                    scroll_delta = 0;
+                                } else if matches!(app.display_mode, DisplayMode::RunAllTests) {
                                     // Cancel run-all or go back to readme if finished
                                     if app.is_running_all_tests {
                                         app.cancel_run_all_tests();
@@ -1436,7 +1997,15 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                                         | DisplayMode::ModelSelection
                                         | DisplayMode::PlaygroundConfirm
                                 ) {
+                                    let was_generating = app.is_generating_hint;
+                                    app.cancel_hint_generation();
                                     app.show_test_output();
+                                    if was_generating {
+                                        app.status_message = format!(
+                                            "Hint generation cancelled | {}",
+                                            app.status_message
+                                        );
+                                    }
                                     scroll_delta = 0;
                                 } else if matches!(
                                     app.display_mode,
@@ -1444,25 +2013,33 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                                         | DisplayMode::TestOutput
                                         | DisplayMode::ReadmeFocused
                                 ) {
+                                    let was_running = app.is_running_test;
                                     app.show_readme();
+                                    if was_running {
+                                        app.set_temp_status(String::from("Test run cancelled"));
+                                    }
                                     scroll_delta = 0;
                                 }
                             }
-                            KeyCode::Char('A') if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                                // Shift+A: Run all tests (only from Readme mode)
-                                if matches!(app.display_mode, DisplayMode::Readme)
-                                    && !app.is_running_all_tests
-                                {
+                            Action::RunAllTests => {
+                                if !app.is_running_all_tests {
                                     app.run_all_tests().await?;
                                     scroll_delta = 0;
                                 }
                             }
-                            KeyCode::Enter => {
+                            Action::Confirm => {
                                 if matches!(app.display_mode, DisplayMode::ModelSelection) {
                                     // Confirm model selection and generate hint
                                     app.confirm_model_selection()?;
-                                    if let Some(model) = app.config.get_model() {
-                                        app.generate_hint_with_model(model.to_string()).await?;
+                                    if let (Some(model), Some(hint_prompt)) =
+                                        (app.config.get_model(), app.hint_prompt())
+                                    {
+                                        app.generate_hint_with_model(
+                                            model.to_string(),
+                                            false,
+                                            hint_prompt,
+                                        )
+                                        .await?;
                                     }
                                     scroll_delta = 0;
                                 } else if matches!(app.display_mode, DisplayMode::EditorSelection) {
@@ -1493,36 +2070,51 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                                     scroll_delta = 0;
                                 }
                             }
-                            KeyCode::Char('r') => {
-                                // Switch to focused README mode with scrolling
-                                if matches!(app.display_mode, DisplayMode::Readme) {
-                                    app.display_mode = DisplayMode::ReadmeFocused;
-                                    app.scroll_position = 0;
-                                    app.status_message = String::from("Reading README | ↑/↓ PgUp/PgDn Home/End - scroll, Esc - back");
+                            // Generate a fresh AI hint instead of the cached one
+                            Action::RegenerateHint => {
+                                if !app.hint_conversation.is_empty() && !app.is_generating_hint {
+                                    app.regenerate_hint().await?;
                                     scroll_delta = 0;
                                 }
                             }
-                            KeyCode::Char('h') => {
-                                // Generate hint if tests have failed
-                                if matches!(app.display_mode, DisplayMode::TestOutput)
-                                    && matches!(app.last_test_result, Some(TestResult::Failed))
-                                    && !app.is_generating_hint
-                                {
-                                    app.check_model_and_generate_hint().await?;
+                            // Switch to focused README mode with scrolling
+                            Action::ReadReadme => {
+                                app.display_mode = DisplayMode::ReadmeFocused;
+                                app.scroll_position = 0;
+                                app.status_message = format!(
+                                    "Reading README | {}",
+                                    app.scroll_hints(&[(Action::Back, "back")])
+                                );
+                                scroll_delta = 0;
+                            }
+                            // Show hints if tests have failed, or reveal the next hint level
+                            Action::Hint => {
+                                let next_level = matches!(app.display_mode, DisplayMode::Hint);
+                                let failed =
+                                    matches!(app.last_test_result, Some(TestResult::Failed));
+                                if !app.is_generating_hint && (next_level || failed) {
+                                    app.request_hint(next_level).await?;
                                     scroll_delta = 0;
                                 }
                             }
-                            KeyCode::Char('p') => {
-                                // Extract to playground if tests passed
-                                if matches!(app.display_mode, DisplayMode::TestOutput)
-                                    && matches!(app.last_test_result, Some(TestResult::Passed))
-                                {
+                            // Ask the tutor a follow-up question
+                            Action::FollowUp => {
+                                app.start_chat_input();
+                                scroll_delta = 0;
+                            }
+                            // Extract to playground if tests passed
+                            Action::Playground => {
+                                if matches!(app.last_test_result, Some(TestResult::Passed)) {
                                     if let Some(exercise) = app.get_selected_exercise() {
                                         // Check if playground already exists
                                         if playground::playground_exists(exercise) {
                                             app.display_mode = DisplayMode::PlaygroundConfirm;
-                                            app.status_message = String::from(
-                                                "Playground exists. Overwrite? y - yes, n - cancel",
+                                            app.status_message = format!(
+                                                "Playground exists. Overwrite? {}",
+                                                app.keymap.hints(&[
+                                                    (Action::Yes, "yes"),
+                                                    (Action::No, "cancel"),
+                                                ])
                                             );
                                         } else {
                                             // Extract directly
@@ -1532,42 +2124,30 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                                     scroll_delta = 0;
                                 }
                             }
-                            KeyCode::Char('y') => {
-                                // Confirm playground overwrite
-                                if matches!(app.display_mode, DisplayMode::PlaygroundConfirm) {
-                                    app.extract_to_playground()?;
-                                    scroll_delta = 0;
-                                }
+                            // Confirm playground overwrite
+                            Action::Yes => {
+                                app.extract_to_playground()?;
+                                scroll_delta = 0;
                             }
-                            KeyCode::Char('n') => {
-                                // Cancel playground overwrite
-                                if matches!(app.display_mode, DisplayMode::PlaygroundConfirm) {
-                                    app.show_test_output();
-                                    scroll_delta = 0;
-                                }
+                            // Cancel playground overwrite
+                            Action::No => {
+                                app.show_test_output();
+                                scroll_delta = 0;
                             }
-                            KeyCode::Char('o') => {
-                                // Open exercise in editor (only from Readme mode)
-                                if matches!(app.display_mode, DisplayMode::Readme) {
-                                    app.open_exercise_in_editor();
-                                    scroll_delta = 0;
-                                }
+                            // Open exercise in editor
+                            Action::OpenEditor => {
+                                app.open_exercise_in_editor();
+                                scroll_delta = 0;
                             }
-                            KeyCode::Char('m') => {
-                                // Manual model selection from hint or test output mode
-                                if matches!(
-                                    app.display_mode,
-                                    DisplayMode::Hint | DisplayMode::TestOutput
-                                ) {
-                                    app.fetch_available_models().await;
-                                    scroll_delta = 0;
-                                } else if matches!(app.display_mode, DisplayMode::ModelSelection) {
-                                    // Refresh model list
-                                    app.fetch_available_models().await;
-                                    scroll_delta = 0;
+                            Action::ChangeModel => {
+                                // Manual model selection from hint or test output mode,
+                                // or refresh the model list
+                                if !matches!(app.display_mode, DisplayMode::ModelSelection) {
+                                    app.cancel_hint_generation();
                                 }
+                                app.fetch_available_models().await;
+                                scroll_delta = 0;
                             }
-                            _ => {}
                         }
                     }
                     _ => {} // Ignore other events
@@ -1586,6 +2166,7 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
                         | DisplayMode::Hint
                         | DisplayMode::RunAllTests
                         | DisplayMode::ReadmeFocused
+                        | DisplayMode::Help
                 )
             {
                 app.apply_scroll_delta(scroll_delta);
@@ -1594,6 +2175,131 @@ async fn run_app_loop<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) ->
     }
 }
 
+/// Every action with its keys, generated from the active keymap
+fn help_lines(keymap: &Keymap) -> Vec<Line<'static>> {
+    let keys: Vec<String> = Action::ALL
+        .iter()
+        .map(|&action| keymap.describe(action))
+        .collect();
+    let width = keys.iter().map(|k| k.chars().count()).max().unwrap_or(0);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "⌨ KEYBINDINGS",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (action, keys) in Action::ALL.iter().zip(keys) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:width$}  ", keys, width = width),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(action.description()),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Change these under \"keybindings\" in `learnp config`",
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines
+}
+
+/// Revealed authored hints followed by the AI hint, if one was requested
+fn hint_view_lines(app: &App) -> Vec<Line<'static>> {
+    let heading = |text: String| {
+        Line::from(Span::styled(
+            text,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        ))
+    };
+    let separator = || {
+        Line::from(Span::styled(
+            "─".repeat(50),
+            Style::default().fg(Color::DarkGray),
+        ))
+    };
+
+    let mut lines = Vec::new();
+    for (index, hint) in app.revealed_hints.iter().enumerate() {
+        lines.push(heading(format!("💡 HINT {}", index + 1)));
+        lines.push(Line::from(""));
+        lines.extend(hint.lines().map(|line| Line::from(line.to_string())));
+        lines.push(Line::from(""));
+        lines.push(separator());
+        lines.push(Line::from(""));
+    }
+
+    // The first message is the prompt with the exercise context; it isn't shown
+    let mut answered = false;
+    for (index, message) in app.hint_conversation.iter().enumerate().skip(1) {
+        match message.role {
+            Role::User => lines.push(heading(String::from("❓ YOU"))),
+            Role::Assistant if answered => lines.push(heading(String::from("💬 TUTOR"))),
+            Role::Assistant => {
+                lines.push(heading(String::from("💡 AI HINT")));
+                answered = true;
+            }
+        }
+        lines.push(Line::from(""));
+        match message.role {
+            Role::User => lines.extend(
+                message
+                    .content
+                    .lines()
+                    .map(|line| Line::from(line.to_string())),
+            ),
+            Role::Assistant => lines.extend(app.rendered_reply(index, &message.content)),
+        }
+        lines.push(Line::from(""));
+    }
+
+    if let Some(reply) = &app.hint_text {
+        let title = if answered {
+            "💬 TUTOR"
+        } else {
+            "💡 AI HINT"
+        };
+        lines.push(heading(String::from(title)));
+        lines.push(Line::from(""));
+
+        if reply.is_empty() && app.is_generating_hint {
+            // Show animated loader while waiting for first token
+            let spinner_frames = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+            let spinner = spinner_frames[(app.blink_counter as usize) % spinner_frames.len()];
+            lines.push(Line::from(vec![
+                Span::styled(
+                    spinner,
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("  Waiting for response..."),
+            ]));
+        } else {
+            // Show the reply as it streams in
+            lines.extend(markdown::render(reply));
+        }
+        lines.push(Line::from(""));
+    }
+
+    if let Some(input) = &app.chat_input {
+        lines.push(separator());
+        lines.push(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Yellow)),
+            Span::raw(input.clone()),
+            Span::styled("▌", Style::default().fg(Color::Yellow)),
+        ]));
+    }
+    lines
+}
+
 fn ui(f: &mut Frame, app: &App) -> usize {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -1655,20 +2361,16 @@ fn ui(f: &mut Frame, app: &App) -> usize {
 }
 
 fn render_exercise_list(f: &mut Frame, app: &App, area: Rect) -> usize {
-    let progress_map: std::collections::HashMap<String, bool> = app
-        .database
-        .get_all_progress()
-        .unwrap_or_default()
-        .into_iter()
-        .map(|p| (p.exercise_id, p.completed))
-        .collect();
 
     let items: Vec<ListItem> = app
         .current_exercises
         .iter()
         .enumerate()
         .map(|(index, exercise)| {
-            let is_completed = progress_map.get(&exercise.id).copied().unwrap_or(false);
+            let ExerciseStatus {
+                completed: is_completed,
+                timed_out,
+            } = app.progress.status(&exercise.id);
 
             let is_running = app.running_exercise_id.as_ref() == Some(&exercise.id);
             let is_locked = !app.is_exercise_unlocked(index);
@@ -1707,6 +2409,8 @@ fn render_exercise_list(f: &mut Frame, app: &App, area: Rect) -> usize {
                     } else {
                         " "
                     }
+                } else if timed_out {
+                    "⏱"
                 } else {
                     " "
                 };
@@ -1720,6 +2424,8 @@ fn render_exercise_list(f: &mut Frame, app: &App, area: Rect) -> usize {
                     Style::default().fg(Color::Green)
                 } else if is_running {
                     Style::default().fg(Color::Yellow)
+                } else if timed_out {
+                    Style::default().fg(Color::Magenta)
                 } else {
                     Style::default().fg(Color::White)
                 };
@@ -1751,39 +2457,16 @@ fn render_exercise_list(f: &mut Frame, app: &App, area: Rect) -> usize {
 fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
     let (content, title, border_color) = match app.display_mode {
         DisplayMode::Readme => {
-            if let Some(exercise) = app.get_selected_exercise() {
-                let mut lines = vec![];
-
-                // Show README content if available
-                if exercise.readme_file.exists() {
-                    if let Ok(readme) = std::fs::read_to_string(&exercise.readme_file) {
-                        for line in readme.lines() {
-                            lines.push(Line::from(line.to_string()));
-                        }
-                    }
-                }
-
-                (Text::from(lines), "README", Color::White)
+            if app.get_selected_exercise().is_some() {
+                (Text::from(app.readme_lines(0)), "README", Color::White)
             } else {
                 (Text::from("No exercise selected"), "README", Color::White)
             }
         }
         DisplayMode::ReadmeFocused => {
-            if let Some(exercise) = app.get_selected_exercise() {
-                let mut all_lines = vec![];
-
-                // Show README content if available
-                if exercise.readme_file.exists() {
-                    if let Ok(readme) = std::fs::read_to_string(&exercise.readme_file) {
-                        for line in readme.lines() {
-                            all_lines.push(Line::from(line.to_string()));
-                        }
-                    }
-                }
-
+            if app.get_selected_exercise().is_some() {
                 // Apply scrolling
-                let visible_lines: Vec<Line> =
-                    all_lines.into_iter().skip(app.scroll_position).collect();
+                let visible_lines = app.readme_lines(app.scroll_position);
 
                 (Text::from(visible_lines), "README", Color::Green)
             } else {
@@ -1816,7 +2499,44 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
                             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
                         )));
                     }
+                    TestResult::Timeout(limit) => {
+                        all_lines.push(Line::from(Span::styled(
+                            format!("⏱ TIMED OUT after {}s", limit.as_secs()),
+                            Style::default()
+                                .fg(Color::Magenta)
+                                .add_modifier(Modifier::BOLD),
+                        )));
+                    }
+                    TestResult::Cancelled => {
+                        all_lines.push(Line::from(Span::styled(
+                            "CANCELLED",
+                            Style::default()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::BOLD),
+                        )));
+                    }
                 }
+
+                // Per-test-case details when the runner's report was parsed
+                if let Some(summary) = &app.last_test_summary {
+                    all_lines.push(Line::from(summary_counts_spans(summary)));
+                    for case in summary.failures() {
+                        all_lines.push(Line::from(""));
+                        all_lines.push(Line::from(Span::styled(
+                            format!("✗ {}", case.name),
+                            Style::default().fg(Color::Red),
+                        )));
+                        if let Some(message) = &case.failure_message {
+                            for line in message.lines().take(MAX_FAILURE_MESSAGE_LINES) {
+                                all_lines.push(Line::from(Span::styled(
+                                    format!("    {}", line),
+                                    Style::default().fg(Color::Gray),
+                                )));
+                            }
+                        }
+                    }
+                }
+
                 all_lines.push(Line::from(""));
                 all_lines.push(Line::from(Span::styled(
                     "─".repeat(50),
@@ -1849,47 +2569,7 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
             (Text::from(visible_lines), "Test Output", Color::White)
         }
         DisplayMode::Hint => {
-            // Show hint header
-            let mut all_lines = vec![
-                Line::from(Span::styled(
-                    "💡 AI HINT",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                )),
-                Line::from(""),
-                Line::from(Span::styled(
-                    "─".repeat(50),
-                    Style::default().fg(Color::DarkGray),
-                )),
-                Line::from(""),
-            ];
-
-            // Show hint text or loading indicator
-            if let Some(hint) = &app.hint_text {
-                if hint.is_empty() {
-                    // Show animated loader while waiting for first token
-                    let spinner_frames = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
-                    let spinner =
-                        spinner_frames[(app.blink_counter as usize) % spinner_frames.len()];
-                    all_lines.push(Line::from(vec![
-                        Span::styled(
-                            spinner,
-                            Style::default()
-                                .fg(Color::Cyan)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::raw("  Waiting for response..."),
-                    ]));
-                } else {
-                    // Show actual hint content as it streams in
-                    for line in hint.lines() {
-                        all_lines.push(Line::from(line.to_string()));
-                    }
-                }
-            } else {
-                all_lines.push(Line::from("Generating hint..."));
-            }
+            let all_lines = hint_view_lines(app);
 
             // Apply manual scrolling by slicing the lines
             let visible_lines: Vec<Line> =
@@ -1926,7 +2606,7 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
-                        .title("Select Ollama Model"),
+                        .title("Select Hint Model"),
                 )
                 .highlight_style(
                     Style::default()
@@ -1939,6 +2619,10 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
             f.render_stateful_widget(list, area, &mut state);
             return;
         }
+        DisplayMode::Search => {
+            render_search(f, app, area);
+            return;
+        }
         DisplayMode::EditorSelection => {
             // Use List widget for editor selection
             let items: Vec<ListItem> = if app.available_editors.is_empty() {
@@ -2004,11 +2688,7 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
             // Progress bar
             let total = app.run_all_progress.len();
             let completed = app.run_all_current_index;
-            let percentage = if total > 0 {
-                (completed * 100) / total
-            } else {
-                0
-            };
+            let percentage = (completed * 100).checked_div(total).unwrap_or(0);
 
             let bar_width = 40;
             let filled = (completed * bar_width) / total.max(1);
@@ -2043,7 +2723,12 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
                     ))
                 } else if line.starts_with("✗") {
                     Line::from(Span::styled(line.as_str(), Style::default().fg(Color::Red)))
-                } else if line.starts_with("Total:") {
+                } else if line.starts_with("⏱") {
+                    Line::from(Span::styled(
+                        line.as_str(),
+                        Style::default().fg(Color::Magenta),
+                    ))
+                } else if line.starts_with("Total:") || line.starts_with("Test cases:") {
                     Line::from(Span::styled(
                         line.as_str(),
                         Style::default()
@@ -2083,6 +2768,14 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
 
             (Text::from(visible_lines), "Run All Tests", Color::White)
         }
+        DisplayMode::Help => {
+            let visible_lines: Vec<Line> = help_lines(&app.keymap)
+                .into_iter()
+                .skip(app.scroll_position)
+                .collect();
+
+            (Text::from(visible_lines), "Keys", Color::Cyan)
+        }
         DisplayMode::PlaygroundConfirm => {
             // Show confirmation prompt
             let all_lines = vec![
@@ -2105,7 +2798,12 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
                 Line::from(""),
                 Line::from(""),
                 Line::from(Span::styled(
-                    "Press 'y' to overwrite, 'n' or 'Esc' to cancel",
+                    format!(
+                        "Press {} to overwrite, {} or {} to cancel",
+                        app.keymap.primary(Action::Yes),
+                        app.keymap.primary(Action::No),
+                        app.keymap.primary(Action::Back)
+                    ),
                     Style::default()
                         .fg(Color::Cyan)
                         .add_modifier(Modifier::BOLD),
@@ -2127,3 +2825,102 @@ fn render_exercise_details(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(paragraph, area);
 }
+
+fn render_search(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let query = Paragraph::new(Line::from(vec![
+        Span::styled("/ ", Style::default().fg(Color::Yellow)),
+        Span::raw(app.search_query.clone()),
+        Span::styled("▌", Style::default().fg(Color::Yellow)),
+    ]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Search Exercises")
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+    f.render_widget(query, chunks[0]);
+
+    let items: Vec<ListItem> = app
+        .search_results
+        .iter()
+        .filter_map(|&index| app.all_exercises.get(index))
+        .map(|exercise| {
+            let (icon, style) = if !app.is_unlocked_in_course(exercise) {
+                ("🔒", Style::default().fg(Color::DarkGray))
+            } else if exercise.is_folder {
+                let (completed, total) = app.get_folder_progress(exercise);
+                if total > 0 && completed == total {
+                    ("✓", Style::default().fg(Color::Green))
+                } else {
+                    ("📁", Style::default().fg(Color::Cyan))
+                }
+            } else if app.progress.status(&exercise.id).completed {
+                ("✓", Style::default().fg(Color::Green))
+            } else {
+                (" ", Style::default().fg(Color::White))
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} {}", icon, exercise.title), style),
+                Span::styled(
+                    format!("  {}", exercise.id),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let title = format!("{} matches", items.len());
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    let mut state = app.search_list_state.clone();
+    f.render_stateful_widget(list, chunks[1], &mut state);
+}
+
+/// Maximum lines of each failure message shown above the raw test output
+const MAX_FAILURE_MESSAGE_LINES: usize = 6;
+
+/// Colored "N passed, N failed, N skipped (Xs)" line for a parsed test report
+fn summary_counts_spans(summary: &TestSummary) -> Vec<Span<'static>> {
+    let mut spans = vec![
+        Span::styled(
+            format!("{} passed", summary.passed),
+            Style::default().fg(Color::Green),
+        ),
+        Span::raw(", "),
+        Span::styled(
+            format!("{} failed", summary.failed),
+            if summary.failed > 0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            },
+        ),
+    ];
+    if summary.skipped > 0 {
+        spans.push(Span::raw(", "));
+        spans.push(Span::styled(
+            format!("{} skipped", summary.skipped),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(duration) = summary.duration {
+        spans.push(Span::styled(
+            format!("  ({:.2}s)", duration.as_secs_f64()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    spans
+}
@@ -0,0 +1,177 @@
+use crate::course::Exercise;
+use crate::test_runner::{TestOutcome, TestResult, TestRunner};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+/// Results of running an exercise's tests against its starter code and its solution
+#[derive(Debug)]
+pub struct Verification {
+    pub starter: TestOutcome,
+    pub solution: TestOutcome,
+}
+
+impl Verification {
+    /// Why the exercise's tests can't be trusted; empty when the starter code fails
+    /// and the solution passes
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        match &self.starter.result {
+            TestResult::Passed => problems.push(String::from("starter code already passes")),
+            TestResult::Error(e) => problems.push(format!("tests error on starter code: {}", e)),
+            _ => {}
+        }
+
+        match &self.solution.result {
+            TestResult::Passed => {}
+            TestResult::Error(e) => problems.push(format!("tests error on solution: {}", e)),
+            TestResult::Timeout(limit) => {
+                problems.push(format!("solution timed out after {}s", limit.as_secs()))
+            }
+            _ => problems.push(String::from("solution does not pass")),
+        }
+
+        problems
+    }
+
+    pub fn was_cancelled(&self) -> bool {
+        self.starter.result == TestResult::Cancelled
+            || self.solution.result == TestResult::Cancelled
+    }
+}
+
+/// Run the exercise's tests on a copy of the starter code, then on a copy with the
+/// solution laid over it. The exercise directory itself is never modified.
+pub async fn verify_exercise(
+    test_runner: &TestRunner,
+    exercise: &Exercise,
+    solution: &Path,
+    cancel_rx: &mut mpsc::Receiver<()>,
+) -> Result<Verification> {
+    let starter_dir = TempDir::new().context("Failed to create a temporary directory")?;
+    copy_tree(&exercise.path, starter_dir.path(), solution)?;
+    let starter = run_in(test_runner, exercise, starter_dir.path(), cancel_rx).await;
+    if starter.result == TestResult::Cancelled {
+        return Ok(Verification {
+            solution: TestOutcome::new(TestResult::Cancelled),
+            starter,
+        });
+    }
+
+    let solution_dir = TempDir::new().context("Failed to create a temporary directory")?;
+    copy_tree(&exercise.path, solution_dir.path(), solution)?;
+    copy_tree(solution, solution_dir.path(), solution)
+        .context(format!("Failed to apply solution from {:?}", solution))?;
+    let solution = run_in(test_runner, exercise, solution_dir.path(), cancel_rx).await;
+
+    Ok(Verification { starter, solution })
+}
+
+/// Run the exercise's tests as if it lived in `dir`
+async fn run_in(
+    test_runner: &TestRunner,
+    exercise: &Exercise,
+    dir: &Path,
+    cancel_rx: &mut mpsc::Receiver<()>,
+) -> TestOutcome {
+    let mut copy = exercise.clone();
+    copy.path = dir.to_path_buf();
+    copy.readme_file = dir.join("README.md");
+
+    // Only the outcome matters here
+    let (output_tx, mut output_rx) = mpsc::channel(100);
+    let drain = tokio::spawn(async move { while output_rx.recv().await.is_some() {} });
+    let outcome = test_runner
+        .run_test_streaming(&copy, output_tx, cancel_rx)
+        .await;
+    let _ = drain.await;
+
+    outcome.unwrap_or_else(|e| {
+        TestOutcome::new(TestResult::Error(format!("Failed to run test: {:#}", e)))
+    })
+}
+
+/// Recursively copy `src` into `dst`, overwriting existing files and leaving out
+/// `exclude`, VCS metadata and playgrounds
+fn copy_tree(src: &Path, dst: &Path, exclude: &Path) -> Result<()> {
+    fs::create_dir_all(dst).with_context(|| format!("Failed to create directory {:?}", dst))?;
+
+    for entry in fs::read_dir(src).with_context(|| format!("Failed to read directory {:?}", src))? {
+        let entry = entry?;
+        let src_path = entry.path();
+        let file_name = entry.file_name();
+        if src_path == exclude || file_name == ".git" || file_name == "playground" {
+            continue;
+        }
+
+        let dst_path = dst.join(&file_name);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_tree(&src_path, &dst_path, exclude)?;
+        } else if file_type.is_file() {
+            fs::copy(&src_path, &dst_path)
+                .with_context(|| format!("Failed to copy {:?} to {:?}", src_path, dst_path))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::course::ExerciseMetadata;
+
+    fn exercise_in(dir: &Path, test_command: &str) -> Exercise {
+        Exercise {
+            metadata: ExerciseMetadata {
+                test_command: Some(test_command.to_string()),
+                ..Default::default()
+            },
+            ..Exercise::for_test("check", dir)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_exercise() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(temp.path().join("answer.txt"), "todo\n").unwrap();
+        std::fs::create_dir_all(temp.path().join("solution")).unwrap();
+        std::fs::write(temp.path().join("solution/answer.txt"), "42\n").unwrap();
+
+        let exercise = exercise_in(temp.path(), "grep -qx 42 answer.txt");
+        let solution = exercise.get_solution_dir().unwrap();
+        let runner = TestRunner::new(temp.path());
+        let (_cancel_tx, mut cancel_rx) = mpsc::channel(1);
+
+        let verification = verify_exercise(&runner, &exercise, &solution, &mut cancel_rx)
+            .await
+            .unwrap();
+        assert_eq!(verification.starter.result, TestResult::Failed);
+        assert_eq!(verification.solution.result, TestResult::Passed);
+        assert!(verification.problems().is_empty());
+
+        // The exercise itself is left untouched
+        let starter = std::fs::read_to_string(temp.path().join("answer.txt")).unwrap();
+        assert_eq!(starter, "todo\n");
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_starter_that_passes() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp.path().join("solution")).unwrap();
+
+        let exercise = exercise_in(temp.path(), "true");
+        let solution = exercise.get_solution_dir().unwrap();
+        let runner = TestRunner::new(temp.path());
+        let (_cancel_tx, mut cancel_rx) = mpsc::channel(1);
+
+        let verification = verify_exercise(&runner, &exercise, &solution, &mut cancel_rx)
+            .await
+            .unwrap();
+        assert_eq!(verification.problems(), ["starter code already passes"]);
+    }
+}